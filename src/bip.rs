@@ -0,0 +1,353 @@
+use crate::atomic::{AtomicUsize, Ordering};
+use crate::validate::check_position;
+use crate::Corruption;
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::slice;
+
+/// Lock-free single-producer single-consumer byte ring that hands out contiguous regions of its
+/// storage, so e.g. a DMA engine can read or write it in place. Holds up to `N` bytes.
+///
+/// Like a bip buffer, a write grant that does not fit before the end of the storage starts over
+/// at the front, and the bytes behind the last committed one are skipped by readers.
+pub struct BipBuffer<const N: usize> {
+    buffer: UnsafeCell<[u8; N]>,
+    /// End of the committed bytes.
+    write: AtomicUsize,
+    /// Start of the bytes not yet released.
+    read: AtomicUsize,
+    /// End of the readable bytes before `write` wrapped to the front.
+    last: AtomicUsize,
+}
+
+unsafe impl<const N: usize> Sync for BipBuffer<N> {}
+
+impl<const N: usize> BipBuffer<N> {
+    /// Number of bytes the buffer can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            buffer: UnsafeCell::new([0; N]),
+            write: AtomicUsize::new(0),
+            read: AtomicUsize::new(0),
+            last: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn split(&mut self) -> (BipProducer<'_, N>, BipConsumer<'_, N>) {
+        (
+            BipProducer {
+                buffer: self,
+                _marker: PhantomData,
+            },
+            BipConsumer {
+                buffer: self,
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// Checks that the positions are in range and that, once writing has wrapped to the front,
+    /// the unread bytes end at the wrap point rather than running into the free region.
+    pub fn debug_validate(&self) -> Result<(), Corruption> {
+        let write = self.write.load(Ordering::Acquire);
+        let read = self.read.load(Ordering::Acquire);
+        let last = self.last.load(Ordering::Acquire);
+        check_position("write", write, N + 1)?;
+        check_position("read", read, N + 1)?;
+        check_position("last", last, N + 1)?;
+        if write < read && read > last {
+            return Err(Corruption::RegionsOverlap { read, write, last });
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    unsafe fn region(&self, start: usize, len: usize) -> *mut u8 {
+        debug_assert!(start + len <= N);
+        unsafe { (self.buffer.get() as *mut u8).add(start) }
+    }
+}
+
+impl<const N: usize> Default for BipBuffer<N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writing half of a [`BipBuffer`].
+pub struct BipProducer<'a, const N: usize> {
+    buffer: &'a BipBuffer<N>,
+    /// Keeps the handle `!Sync`, since only one context may write.
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl<const N: usize> Send for BipProducer<'_, N> {}
+
+impl<const N: usize> BipProducer<'_, N> {
+    /// Reserves `n` contiguous bytes, or returns `None` if no free region is that large.
+    #[inline(always)]
+    pub fn grant_write(&mut self, n: usize) -> Option<WriteGrant<'_, N>> {
+        if n > N {
+            return None;
+        }
+        let buffer = self.buffer;
+        let write = buffer.write.load(Ordering::Relaxed);
+        let read = buffer.read.load(Ordering::Acquire);
+        // Free space is kept strictly apart from unread bytes, so `write == read` always means
+        // empty.
+        let start = if write < read {
+            if write + n < read {
+                write
+            } else {
+                return None;
+            }
+        } else if write + n <= N {
+            write
+        } else if n < read {
+            0
+        } else {
+            return None;
+        };
+        Some(WriteGrant {
+            buffer,
+            start,
+            len: n,
+        })
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+/// Contiguous region reserved by [`BipProducer::grant_write`]. Dropping it commits nothing.
+pub struct WriteGrant<'a, const N: usize> {
+    buffer: &'a BipBuffer<N>,
+    /// Kept as a range rather than a slice, since the reader may touch the bytes as soon as they
+    /// are committed.
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> WriteGrant<'_, N> {
+    #[inline(always)]
+    pub fn buf(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.buffer.region(self.start, self.len), self.len) }
+    }
+
+    /// Makes the first `n` bytes of the grant readable; `n` is capped at the grant's length.
+    #[inline(always)]
+    pub fn commit(self, n: usize) {
+        let buffer = self.buffer;
+        let write = buffer.write.load(Ordering::Relaxed);
+        let new_write = self.start + n.min(self.len);
+        if new_write < write && write != N {
+            // Wrapped to the front, so readers stop where the old bytes end.
+            buffer.last.store(write, Ordering::Release);
+        } else if new_write > buffer.last.load(Ordering::Relaxed) {
+            buffer.last.store(N, Ordering::Release);
+        }
+        buffer.write.store(new_write, Ordering::Release);
+    }
+}
+
+/// Reading half of a [`BipBuffer`].
+pub struct BipConsumer<'a, const N: usize> {
+    buffer: &'a BipBuffer<N>,
+    /// Keeps the handle `!Sync`, since only one context may read.
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl<const N: usize> Send for BipConsumer<'_, N> {}
+
+impl<const N: usize> BipConsumer<'_, N> {
+    /// Grants the oldest contiguous run of committed bytes, or `None` if there are none.
+    #[inline(always)]
+    pub fn read(&mut self) -> Option<ReadGrant<'_, N>> {
+        let buffer = self.buffer;
+        let write = buffer.write.load(Ordering::Acquire);
+        let last = buffer.last.load(Ordering::Acquire);
+        let mut read = buffer.read.load(Ordering::Relaxed);
+        if read == last && write < read {
+            read = 0;
+            buffer.read.store(0, Ordering::Release);
+        }
+        let end = if write < read { last } else { write };
+        let len = end - read;
+        if len == 0 {
+            return None;
+        }
+        Some(ReadGrant {
+            buffer,
+            start: read,
+            len,
+        })
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+/// Contiguous committed bytes granted by [`BipConsumer::read`]. Dropping it releases nothing.
+pub struct ReadGrant<'a, const N: usize> {
+    buffer: &'a BipBuffer<N>,
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> ReadGrant<'_, N> {
+    #[inline(always)]
+    pub fn buf(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.buffer.region(self.start, self.len), self.len) }
+    }
+
+    /// Frees the first `n` bytes of the grant for writing; `n` is capped at the grant's length.
+    #[inline(always)]
+    pub fn release(self, n: usize) {
+        let read = self.start + n.min(self.len);
+        self.buffer.read.store(read, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_grant_commit_read_release() {
+        let mut ring: BipBuffer<8> = BipBuffer::new();
+        let (mut producer, mut consumer) = ring.split();
+        assert!(consumer.read().is_none());
+        assert!(producer.grant_write(9).is_none());
+
+        let mut grant = producer.grant_write(4).unwrap();
+        grant.buf().copy_from_slice(b"abcd");
+        grant.commit(3);
+        let grant = consumer.read().unwrap();
+        assert_eq!(grant.buf(), b"abc");
+        grant.release(2);
+
+        // An uncommitted grant is handed out again.
+        {
+            let mut grant = producer.grant_write(5).unwrap();
+            grant.buf().copy_from_slice(b"efghi");
+        }
+        let mut grant = producer.grant_write(5).unwrap();
+        grant.buf()[..2].copy_from_slice(b"xy");
+        grant.commit(2);
+        assert!(producer.grant_write(4).is_none());
+        let grant = consumer.read().unwrap();
+        assert_eq!(grant.buf(), b"cxy");
+        grant.release(3);
+        assert!(consumer.read().is_none());
+    }
+
+    #[test]
+    fn test_debug_validate_detects_overlap() {
+        let ring: BipBuffer<8> = BipBuffer::new();
+        assert_eq!(ring.debug_validate(), Ok(()));
+        ring.write.store(2, Ordering::Relaxed);
+        ring.read.store(6, Ordering::Relaxed);
+        ring.last.store(5, Ordering::Relaxed);
+        assert_eq!(
+            ring.debug_validate(),
+            Err(Corruption::RegionsOverlap {
+                read: 6,
+                write: 2,
+                last: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_wraps_to_front() {
+        let mut ring: BipBuffer<8> = BipBuffer::new();
+        let (mut producer, mut consumer) = ring.split();
+        let mut grant = producer.grant_write(6).unwrap();
+        grant.buf().copy_from_slice(b"abcdef");
+        grant.commit(6);
+        consumer.read().unwrap().release(4);
+
+        let mut grant = producer.grant_write(3).unwrap();
+        grant.buf().copy_from_slice(b"xyz");
+        grant.commit(3);
+        // The two bytes behind `f` were never written and are skipped.
+        let grant = consumer.read().unwrap();
+        assert_eq!(grant.buf(), b"ef");
+        grant.release(2);
+        let grant = consumer.read().unwrap();
+        assert_eq!(grant.buf(), b"xyz");
+        grant.release(3);
+        assert!(consumer.read().is_none());
+    }
+
+    #[test]
+    fn test_dropped_grant_leaves_positions_alone() {
+        let mut ring: BipBuffer<8> = BipBuffer::new();
+        let (mut producer, mut consumer) = ring.split();
+        producer.grant_write(6).unwrap().commit(6);
+        consumer.read().unwrap().release(4);
+
+        // This grant wraps to the front; dropping it must not publish the wrap.
+        assert!(producer.grant_write(3).is_some());
+        assert_eq!(consumer.read().unwrap().buf().len(), 2);
+        assert!(producer.grant_write(2).is_some());
+        let grant = producer.grant_write(2).unwrap();
+        grant.commit(2);
+        assert_eq!(consumer.read().unwrap().buf().len(), 4);
+    }
+
+    #[test]
+    fn test_across_threads() {
+        let mut ring: BipBuffer<16> = BipBuffer::new();
+        let (mut producer, mut consumer) = ring.split();
+        thread::scope(|s| {
+            s.spawn(move || {
+                let mut next = 0u8;
+                while next < 200 {
+                    let n = (next as usize % 5) + 1;
+                    match producer.grant_write(n) {
+                        Some(mut grant) => {
+                            for byte in grant.buf() {
+                                *byte = next;
+                                next += 1;
+                            }
+                            grant.commit(n);
+                        }
+                        None => thread::yield_now(),
+                    }
+                }
+            });
+            let mut expected = 0u8;
+            while expected < 200 {
+                match consumer.read() {
+                    Some(grant) => {
+                        let len = grant.buf().len();
+                        for &byte in grant.buf() {
+                            assert_eq!(byte, expected);
+                            expected += 1;
+                        }
+                        grant.release(len);
+                    }
+                    None => thread::yield_now(),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{Reject, StorageQueue};
+use alloc::boxed::Box;
+use core::mem::MaybeUninit;
+
+/// Heap-allocated FIFO queue with the same methods as [`crate::Queue`], for host-side tools
+/// and tests. Holds the capacity given to [`StorageQueue::with_capacity`].
+pub type BoxedQueue<T, I = usize, P = Reject> = StorageQueue<T, Box<[MaybeUninit<T>]>, I, P>;
+
+impl<T> BoxedQueue<T> {
+    /// Other index types and policies go through [`StorageQueue::from_storage`].
+    #[inline(always)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_storage(Box::new_uninit_slice(capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::Queue;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_matches_static_queue() {
+        let mut boxed = BoxedQueue::with_capacity(5);
+        let mut fixed: Queue<u32, 5> = Queue::new();
+        let mut out = [0; 3];
+
+        for round in 0..10 {
+            assert_eq!(
+                boxed.enqueue_slice(&[round, round + 1, round + 2]),
+                fixed.enqueue_slice(&[round, round + 1, round + 2])
+            );
+            assert_eq!(
+                boxed.enqueue_overwrite(round),
+                fixed.enqueue_overwrite(round)
+            );
+            assert_eq!(boxed.try_enqueue(4711), fixed.try_enqueue(4711));
+            assert!(boxed.iter().eq(fixed.iter()));
+            assert_eq!(boxed.as_slices(), fixed.as_slices());
+            assert_eq!(boxed.dequeue_into(&mut out), fixed.dequeue_into(&mut out));
+            assert_eq!(boxed.dequeue(), fixed.dequeue());
+            assert_eq!(boxed.len(), fixed.len());
+        }
+    }
+
+    #[test]
+    fn test_shares_the_full_queue_surface() {
+        let mut boxed = BoxedQueue::with_capacity(6);
+        let mut fixed: Queue<u32, 6> = Queue::new();
+        boxed.extend([26769, 64004, 63460, 857, 4711, 7690]);
+        fixed.extend([26769, 64004, 63460, 857, 4711, 7690]);
+
+        assert_eq!(boxed.advance(2), fixed.advance(2));
+        boxed.extend([1, 2]);
+        fixed.extend([1, 2]);
+        assert_eq!(boxed.remove(1), fixed.remove(1));
+        boxed.retain(|&x| x != 4711);
+        fixed.retain(|&x| x != 4711);
+        boxed[0] += 1;
+        fixed[0] += 1;
+        assert_eq!(boxed.make_contiguous(), fixed.make_contiguous());
+        assert_eq!(boxed.split_off(2), fixed.split_off(2));
+        assert_eq!(boxed, fixed);
+
+        let mut batches = boxed.chunks(1);
+        assert_eq!(batches.next().map(|batch| batch.capacity()), Some(6));
+        assert!(batches.eq(fixed.chunks(1).skip(1)));
+        assert!(boxed.is_empty());
+
+        boxed.extend([3, 4, 5]);
+        assert_eq!(boxed.transfer(&mut fixed, 2), 2);
+        assert!(fixed.iter().eq(&[3, 4]));
+        assert!(boxed.drain().eq([5]));
+        #[cfg(feature = "stats")]
+        assert_eq!((boxed.stats().enqueued, boxed.stats().dequeued), (11, 11));
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let mut queue = BoxedQueue::with_capacity(3);
+        for _ in 0..4 {
+            let _ = queue.try_enqueue(token.clone());
+        }
+        assert_eq!(Rc::strong_count(&token), 4);
+        drop(queue.dequeue());
+        queue.enqueue(token.clone());
+        let copy = queue.clone();
+        assert_eq!(copy, queue);
+        drop((queue, copy));
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_wipes_heap_slots() {
+        fn raw(queue: &BoxedQueue<u8>) -> [u8; 4] {
+            core::array::from_fn(|i| unsafe { queue.buffer[i].assume_init() })
+        }
+
+        let mut queue = BoxedQueue::with_capacity(4);
+        queue.extend([1, 2, 3, 4]);
+        queue.dequeue();
+        assert_eq!(raw(&queue), [0, 2, 3, 4]);
+        queue.truncate(2);
+        assert_eq!(raw(&queue), [0, 2, 3, 0]);
+        queue.enqueue(5);
+        queue.clear();
+        assert_eq!(raw(&queue), [0; 4]);
+    }
+}
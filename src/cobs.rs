@@ -0,0 +1,150 @@
+use crate::{Policy, QueueIndex, Storage, StorageQueue};
+use core::mem::MaybeUninit;
+
+/// Longest run a COBS code byte can describe, which is encoded as `0xFF`.
+const MAX_RUN: usize = 254;
+
+/// Bytes `payload` takes once COBS encoded, including the zero delimiter.
+fn encoded_len(payload: &[u8]) -> usize {
+    let mut len = 2;
+    let mut run = 0;
+    for (i, &byte) in payload.iter().enumerate() {
+        len += 1;
+        run = if byte == 0 { 0 } else { run + 1 };
+        if run == MAX_RUN && i + 1 < payload.len() {
+            len += 1;
+            run = 0;
+        }
+    }
+    len
+}
+
+/// COBS framing with a zero delimiter, encoded and decoded in place in the ring.
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> StorageQueue<u8, S, I, P> {
+    /// Overwrites the byte `n` elements behind the head.
+    #[inline(always)]
+    fn set(&mut self, n: usize, byte: u8) {
+        let slot = self.slot(n);
+        self.slots_mut()[slot] = MaybeUninit::new(byte);
+    }
+
+    /// Enqueues `payload` COBS encoded and followed by a zero delimiter, or returns `false`
+    /// without enqueueing anything if the encoded frame does not fit.
+    pub fn enqueue_frame_cobs(&mut self, payload: &[u8]) -> bool {
+        if encoded_len(payload) > self.remaining_capacity() {
+            return false;
+        }
+        let mut code_at = self.len();
+        let mut code = 1;
+        self.enqueue(0);
+        for (i, &byte) in payload.iter().enumerate() {
+            if byte != 0 {
+                self.enqueue(byte);
+                code += 1;
+            }
+            if byte == 0 || (code as usize == MAX_RUN + 1 && i + 1 < payload.len()) {
+                self.set(code_at, code);
+                code_at = self.len();
+                code = 1;
+                self.enqueue(0);
+            }
+        }
+        self.set(code_at, code);
+        self.enqueue(0);
+        true
+    }
+
+    /// Dequeues the oldest complete COBS frame, decoding it into `out`, and returns the decoded
+    /// length. Returns `None` while no delimiter has been enqueued. If `out` is shorter, only the
+    /// leading bytes are kept. A code byte pointing past the delimiter ends the frame early.
+    pub fn dequeue_frame_cobs(&mut self, out: &mut [u8]) -> Option<usize> {
+        let end = self.iter().position(|&byte| byte == 0)?;
+        let mut decoded = 0;
+        let mut push = |byte| {
+            if let Some(slot) = out.get_mut(decoded) {
+                *slot = byte;
+            }
+            decoded += 1;
+        };
+        let mut bytes = self.iter().take(end).copied();
+        while let Some(code) = bytes.next() {
+            for byte in bytes.by_ref().take(code as usize - 1) {
+                push(byte);
+            }
+            if code as usize <= MAX_RUN && bytes.len() > 0 {
+                push(0);
+            }
+        }
+        self.advance(end + 1);
+        Some(decoded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{Queue, QueueRef};
+    use std::vec::Vec;
+
+    fn encode(payload: &[u8]) -> Vec<u8> {
+        let mut queue: Queue<u8, 600> = Queue::new();
+        assert!(queue.enqueue_frame_cobs(payload));
+        assert_eq!(queue.len(), encoded_len(payload));
+        queue.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_cobs_encoding() {
+        assert_eq!(encode(&[]), [0x01, 0x00]);
+        assert_eq!(encode(&[0x00]), [0x01, 0x01, 0x00]);
+        assert_eq!(
+            encode(&[0x11, 0x22, 0x00, 0x33]),
+            [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]
+        );
+        assert_eq!(encode(&[0x11, 0x00, 0x00]), [0x02, 0x11, 0x01, 0x01, 0x00]);
+
+        let run: Vec<u8> = (1..=254).collect();
+        let mut expected = std::vec![0xFF];
+        expected.extend(&run);
+        expected.push(0x00);
+        assert_eq!(encode(&run), expected);
+
+        let run: Vec<u8> = (1..=255).collect();
+        let mut expected = std::vec![0xFF];
+        expected.extend(&run[..254]);
+        expected.extend([0x02, 0xFF, 0x00]);
+        assert_eq!(encode(&run), expected);
+    }
+
+    #[test]
+    fn test_cobs_round_trip_across_wrap() {
+        let mut queue: Queue<u8, 16> = Queue::new();
+        let mut out = [0; 16];
+        assert_eq!(queue.dequeue_frame_cobs(&mut out), None);
+
+        for round in 0..10u8 {
+            let payload = [round, 0, round, round, 0];
+            assert!(queue.enqueue_frame_cobs(&payload));
+            assert!(queue.enqueue_frame_cobs(&[]));
+            assert_eq!(queue.dequeue_frame_cobs(&mut out), Some(5));
+            assert_eq!(out[..5], payload);
+            assert_eq!(queue.dequeue_frame_cobs(&mut out), Some(0));
+            assert!(queue.is_empty());
+        }
+        assert!(!queue.enqueue_frame_cobs(&[1; 15]));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_cobs_in_borrowed_buffer() {
+        let mut arena = [const { MaybeUninit::uninit() }; 8];
+        let mut queue = QueueRef::new(&mut arena[..]);
+        assert!(queue.enqueue_frame_cobs(&[0x11, 0x00, 0x22]));
+        assert!(queue.iter().eq(&[0x02, 0x11, 0x02, 0x22, 0x00]));
+        let mut out = [0; 8];
+        assert_eq!(queue.dequeue_frame_cobs(&mut out), Some(3));
+        assert_eq!(out[..3], [0x11, 0x00, 0x22]);
+    }
+}
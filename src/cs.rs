@@ -0,0 +1,479 @@
+use crate::waiter::{Waiter, WaiterList};
+use crate::{Queue, QueueFull};
+use ::critical_section::Mutex;
+use core::cell::{Cell, RefCell, UnsafeCell};
+use core::future::poll_fn;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+
+/// [`Queue`] behind a critical section, so it can be declared as a plain `static` and shared
+/// between interrupt handlers and thread context. Holds `N` elements.
+///
+/// [`CsQueue::dequeue_async`] and [`CsQueue::enqueue_async`] let it double as a small channel
+/// between async tasks. Any number of tasks may wait on either side at once; they are woken
+/// in the order they started waiting.
+pub struct CsQueue<T, const N: usize> {
+    shared: Mutex<RefCell<Shared<T, N>>>,
+}
+
+struct Shared<T, const N: usize> {
+    queue: Queue<T, N>,
+    /// Tasks in [`CsQueue::dequeue_async`].
+    dequeue_waiters: WaiterList,
+    /// Tasks in [`CsQueue::enqueue_async`].
+    enqueue_waiters: WaiterList,
+    /// Task polling the queue as a `Stream`, which has nowhere to keep a waiter node. There is
+    /// room for one, so a second stream polling at the same time takes its place.
+    dequeue_waker: Option<Waker>,
+    /// Task polling the queue as a `Sink`, likewise one at a time.
+    enqueue_waker: Option<Waker>,
+}
+
+impl<T, const N: usize> Shared<T, N> {
+    /// Takes the wakers whose task can now make progress, to be woken after the critical section.
+    /// Only one waiter on each side is woken; once it has taken its turn, the next one is woken
+    /// if there is still something for it.
+    #[inline(always)]
+    fn ready_wakers(&mut self) -> [Option<Waker>; 4] {
+        let (len, free) = (self.queue.len(), self.queue.remaining_capacity());
+        [
+            self.dequeue_waiters.pop(len),
+            (len > 0).then(|| self.dequeue_waker.take()).flatten(),
+            self.enqueue_waiters.pop(free),
+            (free > 0).then(|| self.enqueue_waker.take()).flatten(),
+        ]
+    }
+
+    #[inline(always)]
+    fn waiters(&mut self, side: Side) -> &mut WaiterList {
+        match side {
+            Side::Dequeue => &mut self.dequeue_waiters,
+            Side::Enqueue => &mut self.enqueue_waiters,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Dequeue,
+    Enqueue,
+}
+
+/// A waiting task's place in one of the queue's [`WaiterList`]s. Dropping a future that is
+/// still waiting unlinks it, and if it had already been woken, passes the wakeup on to the
+/// next waiter so none is lost.
+struct Wait<'a, T, const N: usize> {
+    queue: &'a CsQueue<T, N>,
+    side: Side,
+    node: UnsafeCell<Waiter>,
+    /// Set once the future has completed and is no longer linked.
+    done: Cell<bool>,
+}
+
+impl<'a, T, const N: usize> Wait<'a, T, N> {
+    #[inline(always)]
+    fn new(queue: &'a CsQueue<T, N>, side: Side, need: usize) -> Self {
+        Self {
+            queue,
+            side,
+            node: UnsafeCell::new(Waiter::new(need)),
+            done: Cell::new(false),
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for Wait<'_, T, N> {
+    fn drop(&mut self) {
+        if self.done.get() {
+            return;
+        }
+        let wakers = self.queue.lock(|shared| {
+            unsafe { shared.waiters(self.side).remove(self.node.get()) };
+            shared.ready_wakers()
+        });
+        wakers.into_iter().flatten().for_each(Waker::wake);
+    }
+}
+
+/// Stores `waker` in `slot`. The waker it displaces is dropped rather than woken: waking it
+/// would make two tasks polling the same side take the slot from each other in a busy loop.
+#[cfg(feature = "futures")]
+#[inline(always)]
+fn register(slot: &mut Option<Waker>, waker: &Waker) {
+    match slot {
+        Some(registered) if registered.will_wake(waker) => {}
+        _ => *slot = Some(waker.clone()),
+    }
+}
+
+impl<T, const N: usize> CsQueue<T, N> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            shared: Mutex::new(RefCell::new(Shared {
+                queue: Queue::new(),
+                dequeue_waiters: WaiterList::new(),
+                enqueue_waiters: WaiterList::new(),
+                dequeue_waker: None,
+                enqueue_waker: None,
+            })),
+        }
+    }
+
+    #[inline(always)]
+    fn lock<R>(&self, f: impl FnOnce(&mut Shared<T, N>) -> R) -> R {
+        ::critical_section::with(|cs| f(&mut self.shared.borrow(cs).borrow_mut()))
+    }
+
+    /// Runs `f` on the inner queue inside a critical section, for operations that need several
+    /// steps to happen atomically. Calling back into this queue from `f` panics.
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(&mut Queue<T, N>) -> R) -> R {
+        let (result, wakers) = self.lock(|shared| {
+            let result = f(&mut shared.queue);
+            (result, shared.ready_wakers())
+        });
+        wakers.into_iter().flatten().for_each(Waker::wake);
+        result
+    }
+
+    #[inline(always)]
+    pub fn try_enqueue(&self, item: T) -> Result<(), QueueFull<T>> {
+        self.with(|queue| queue.try_enqueue(item))
+    }
+
+    #[inline(always)]
+    pub fn dequeue(&self) -> Option<T> {
+        self.with(|queue| queue.dequeue())
+    }
+
+    /// Waits until there is room for `item`.
+    pub async fn enqueue_async(&self, item: T) {
+        let mut item = Some(item);
+        let wait = pin!(Wait::new(self, Side::Enqueue, 1));
+        poll_fn(|cx| self.poll_enqueue(cx, &mut item, &wait)).await
+    }
+
+    /// Waits until at least `n` slots are free, so a producer can hold back a burst until it
+    /// fits. Another context may still take the slots before this task fills them. Panics if
+    /// `n` exceeds [`Self::CAPACITY`], since that never happens.
+    pub async fn wait_for_space(&self, n: usize) {
+        assert!(n <= N, "waiting for more space than the queue has");
+        let wait = pin!(Wait::new(self, Side::Enqueue, n));
+        poll_fn(|cx| self.poll_space(cx, n, &wait)).await
+    }
+
+    fn poll_space(&self, cx: &mut Context<'_>, n: usize, wait: &Wait<'_, T, N>) -> Poll<()> {
+        self.lock(|shared| {
+            if shared.queue.remaining_capacity() >= n {
+                unsafe { shared.enqueue_waiters.remove(wait.node.get()) };
+                wait.done.set(true);
+                Poll::Ready(())
+            } else {
+                unsafe { shared.enqueue_waiters.register(wait.node.get(), cx.waker()) };
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Waits until there is an element to dequeue.
+    pub async fn dequeue_async(&self) -> T {
+        let wait = pin!(Wait::new(self, Side::Dequeue, 1));
+        poll_fn(|cx| self.poll_dequeue_waiting(cx, &wait)).await
+    }
+
+    fn poll_enqueue(
+        &self,
+        cx: &mut Context<'_>,
+        item: &mut Option<T>,
+        wait: &Wait<'_, T, N>,
+    ) -> Poll<()> {
+        let (poll, wakers) = self.lock(|shared| {
+            let poll = match item.take().map(|item| shared.queue.try_enqueue(item)) {
+                Some(Err(QueueFull(rejected))) => {
+                    *item = Some(rejected);
+                    unsafe { shared.enqueue_waiters.register(wait.node.get(), cx.waker()) };
+                    Poll::Pending
+                }
+                _ => {
+                    unsafe { shared.enqueue_waiters.remove(wait.node.get()) };
+                    wait.done.set(true);
+                    Poll::Ready(())
+                }
+            };
+            (poll, shared.ready_wakers())
+        });
+        wakers.into_iter().flatten().for_each(Waker::wake);
+        poll
+    }
+
+    fn poll_dequeue_waiting(&self, cx: &mut Context<'_>, wait: &Wait<'_, T, N>) -> Poll<T> {
+        let (poll, wakers) = self.lock(|shared| {
+            let poll = match shared.queue.dequeue() {
+                Some(item) => {
+                    unsafe { shared.dequeue_waiters.remove(wait.node.get()) };
+                    wait.done.set(true);
+                    Poll::Ready(item)
+                }
+                None => {
+                    unsafe { shared.dequeue_waiters.register(wait.node.get(), cx.waker()) };
+                    Poll::Pending
+                }
+            };
+            (poll, shared.ready_wakers())
+        });
+        wakers.into_iter().flatten().for_each(Waker::wake);
+        poll
+    }
+
+    /// Ready once there is room for another element. Only the last task to get `Pending` is
+    /// woken, so one task at a time should poll the queue as a `Sink`.
+    #[cfg(feature = "futures")]
+    pub(crate) fn poll_free_slot(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.lock(|shared| match shared.queue.is_full() {
+            true => {
+                register(&mut shared.enqueue_waker, cx.waker());
+                Poll::Pending
+            }
+            false => Poll::Ready(()),
+        })
+    }
+
+    /// Dequeues an element, or registers to be woken once there is one. Only the last task to
+    /// get `Pending` is woken, so one task at a time should poll the queue as a `Stream`.
+    #[cfg(feature = "futures")]
+    pub(crate) fn poll_dequeue(&self, cx: &mut Context<'_>) -> Poll<T> {
+        let (poll, wakers) = self.lock(|shared| {
+            let poll = match shared.queue.dequeue() {
+                Some(item) => Poll::Ready(item),
+                None => {
+                    register(&mut shared.dequeue_waker, cx.waker());
+                    Poll::Pending
+                }
+            };
+            (poll, shared.ready_wakers())
+        });
+        wakers.into_iter().flatten().for_each(Waker::wake);
+        poll
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.lock(|shared| shared.queue.len())
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.lock(|shared| shared.queue.is_empty())
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.lock(|shared| shared.queue.is_full())
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize> Default for CsQueue<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use core::future::Future;
+    use std::boxed::Box;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_static_enqueue_dequeue() {
+        static QUEUE: CsQueue<u32, 3> = CsQueue::new();
+        assert_eq!(QUEUE.dequeue(), None);
+
+        for round in 0..5 {
+            assert_eq!(QUEUE.try_enqueue(round), Ok(()));
+            assert_eq!(QUEUE.try_enqueue(round + 1), Ok(()));
+            assert_eq!(QUEUE.try_enqueue(round + 2), Ok(()));
+            assert_eq!(QUEUE.try_enqueue(4711), Err(QueueFull(4711)));
+            assert!(QUEUE.is_full());
+            assert_eq!(QUEUE.with(|queue| queue.peek().copied()), Some(round));
+            assert_eq!(QUEUE.dequeue(), Some(round));
+            assert_eq!(QUEUE.dequeue(), Some(round + 1));
+            assert_eq!(QUEUE.dequeue(), Some(round + 2));
+            assert!(QUEUE.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_across_threads() {
+        static QUEUE: CsQueue<u32, 4> = CsQueue::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 0..1000 {
+                    while QUEUE.try_enqueue(i).is_err() {
+                        thread::yield_now();
+                    }
+                }
+            });
+            for i in 0..1000 {
+                loop {
+                    if let Some(item) = QUEUE.dequeue() {
+                        assert_eq!(item, i);
+                        break;
+                    }
+                    thread::yield_now();
+                }
+            }
+        });
+        assert!(QUEUE.is_empty());
+    }
+
+    #[test]
+    fn test_async_across_threads() {
+        static QUEUE: CsQueue<u32, 2> = CsQueue::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                block_on(async {
+                    for i in 0..1000 {
+                        QUEUE.enqueue_async(i).await;
+                    }
+                })
+            });
+            block_on(async {
+                for i in 0..1000 {
+                    assert_eq!(QUEUE.dequeue_async().await, i);
+                }
+            });
+        });
+        assert!(QUEUE.is_empty());
+    }
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_waiters_woken_in_order() {
+        let queue: CsQueue<u32, 2> = CsQueue::new();
+        let counters = [(); 2].map(|_| Arc::new(CountingWaker(AtomicUsize::new(0))));
+        let wakers = counters.clone().map(Waker::from);
+        let woken = || {
+            counters
+                .each_ref()
+                .map(|counter| counter.0.load(Ordering::Relaxed))
+        };
+
+        let mut first = Box::pin(queue.dequeue_async());
+        let mut second = pin!(queue.dequeue_async());
+        let mut cx = Context::from_waker(&wakers[0]);
+        assert_eq!(first.as_mut().poll(&mut cx), Poll::Pending);
+        let mut cx = Context::from_waker(&wakers[1]);
+        assert_eq!(second.as_mut().poll(&mut cx), Poll::Pending);
+
+        queue.try_enqueue(1).unwrap();
+        assert_eq!(woken(), [1, 0]);
+        // The first task gives up, so its wakeup goes to the second.
+        drop(first);
+        assert_eq!(woken(), [1, 1]);
+        assert_eq!(second.as_mut().poll(&mut cx), Poll::Ready(1));
+    }
+
+    #[test]
+    fn test_wait_for_space() {
+        let queue: CsQueue<u32, 3> = CsQueue::new();
+        let counters = [(); 2].map(|_| Arc::new(CountingWaker(AtomicUsize::new(0))));
+        let wakers = counters.clone().map(Waker::from);
+        let woken = || {
+            counters
+                .each_ref()
+                .map(|counter| counter.0.load(Ordering::Relaxed))
+        };
+        for item in 0..3 {
+            queue.try_enqueue(item).unwrap();
+        }
+
+        let mut burst = pin!(queue.wait_for_space(2));
+        let mut single = pin!(queue.enqueue_async(3));
+        let mut cx = [0, 1].map(|i| Context::from_waker(&wakers[i]));
+        assert_eq!(burst.as_mut().poll(&mut cx[0]), Poll::Pending);
+        assert_eq!(single.as_mut().poll(&mut cx[1]), Poll::Pending);
+
+        // One free slot is enough for the single item but not the burst.
+        assert_eq!(queue.dequeue(), Some(0));
+        assert_eq!(woken(), [0, 1]);
+        assert_eq!(single.as_mut().poll(&mut cx[1]), Poll::Ready(()));
+        assert_eq!(queue.dequeue(), Some(1));
+        assert_eq!(queue.dequeue(), Some(2));
+        assert_eq!(woken(), [1, 1]);
+        assert_eq!(burst.as_mut().poll(&mut cx[0]), Poll::Ready(()));
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn test_many_consumers_across_threads() {
+        static QUEUE: CsQueue<u32, 2> = CsQueue::new();
+        static SUM: AtomicUsize = AtomicUsize::new(0);
+        thread::scope(|s| {
+            for _ in 0..3 {
+                s.spawn(|| {
+                    block_on(async {
+                        for _ in 0..300 {
+                            let item = QUEUE.dequeue_async().await;
+                            SUM.fetch_add(item as usize, Ordering::Relaxed);
+                        }
+                    })
+                });
+            }
+            for producer in 0..3 {
+                s.spawn(move || {
+                    block_on(async {
+                        for i in 0..300 {
+                            QUEUE.enqueue_async(producer * 300 + i).await;
+                        }
+                    })
+                });
+            }
+        });
+        assert_eq!(SUM.load(Ordering::Relaxed), (0..900).sum());
+        assert!(QUEUE.is_empty());
+    }
+}
@@ -0,0 +1,16 @@
+use crate::{Policy, QueueIndex, Storage, StorageQueue};
+use ::defmt::{Format, Formatter};
+
+/// Logs the length followed by the live elements, oldest first.
+impl<T: Format, S: Storage<T>, I: QueueIndex, P: Policy> Format for StorageQueue<T, S, I, P> {
+    fn format(&self, f: Formatter<'_>) {
+        ::defmt::write!(f, "Queue {{ len: {=usize}, items: [", self.len());
+        for (i, item) in self.iter().enumerate() {
+            if i != 0 {
+                ::defmt::write!(f, ", ");
+            }
+            ::defmt::write!(f, "{}", item);
+        }
+        ::defmt::write!(f, "] }}");
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{wrap, Iter, IterMut, Queue, QueueFull, QueueIndex};
+use core::fmt;
+
+/// Statically allocated double-ended queue, built on the same ring as [`Queue`]. Holds `N`
+/// elements.
+pub struct Deque<T, const N: usize, I: QueueIndex = usize> {
+    ring: Queue<T, N, I>,
+}
+
+impl<T, const N: usize, I: QueueIndex> Deque<T, N, I> {
+    /// Number of elements the deque can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { ring: Queue::new() }
+    }
+
+    #[inline(always)]
+    pub fn push_back(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        self.ring.try_enqueue(item)
+    }
+
+    #[inline(always)]
+    pub fn push_front(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        if self.ring.is_full() {
+            return Err(QueueFull(item));
+        }
+        let head = wrap::<N>(self.ring.head() + N - 1);
+        self.ring.buffer[head].write(item);
+        self.ring.head = I::from_usize(head);
+        self.ring.len = I::from_usize(self.ring.len() + 1);
+        #[cfg(feature = "stats")]
+        self.ring.stats.enqueued(1, self.ring.len(), N);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.ring.dequeue()
+    }
+
+    #[inline(always)]
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.ring.take_back()
+    }
+
+    #[inline(always)]
+    pub fn front(&self) -> Option<&T> {
+        self.ring.peek()
+    }
+
+    #[inline(always)]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.ring.peek_mut()
+    }
+
+    #[inline(always)]
+    pub fn back(&self) -> Option<&T> {
+        self.ring.back()
+    }
+
+    #[inline(always)]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.ring.back_mut()
+    }
+
+    /// Returns the `n`-th element, where `get(0)` is the front.
+    #[inline(always)]
+    pub fn get(&self, n: usize) -> Option<&T> {
+        self.ring.get(n)
+    }
+
+    /// Iterates from front to back.
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.ring.iter()
+    }
+
+    #[inline(always)]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.ring.iter_mut()
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ring.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex> Default for Deque<T, N, I> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize, I: QueueIndex> Clone for Deque<T, N, I> {
+    fn clone(&self) -> Self {
+        Self {
+            ring: self.ring.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, I: QueueIndex> fmt::Debug for Deque<T, N, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_push_pop_both_ends() {
+        let mut deque: Deque<u32, 3> = Deque::new();
+        assert_eq!(deque.pop_front(), None);
+        assert_eq!(deque.pop_back(), None);
+
+        for round in 0..5 {
+            assert_eq!(deque.push_back(round + 1), Ok(()));
+            assert_eq!(deque.push_front(round), Ok(()));
+            assert_eq!(deque.push_back(round + 2), Ok(()));
+            assert_eq!(deque.push_front(4711), Err(QueueFull(4711)));
+            assert_eq!(deque.front(), Some(&round));
+            assert_eq!(deque.back(), Some(&(round + 2)));
+            assert!(deque.iter().copied().eq([round, round + 1, round + 2]));
+
+            assert_eq!(deque.pop_back(), Some(round + 2));
+            assert_eq!(deque.pop_front(), Some(round));
+            assert_eq!(deque.pop_back(), Some(round + 1));
+            assert!(deque.is_empty());
+        }
+        #[cfg(feature = "stats")]
+        assert_eq!(
+            (deque.ring.stats().enqueued, deque.ring.stats().dequeued),
+            (15, 15)
+        );
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let mut deque: Deque<Rc<()>, 4, u8> = Deque::new();
+        deque.push_front(token.clone()).unwrap();
+        deque.push_back(token.clone()).unwrap();
+        deque.push_front(token.clone()).unwrap();
+        drop(deque.pop_back());
+        assert_eq!(Rc::strong_count(&token), 3);
+        drop(deque);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{Policy, QueueIndex, Storage, StorageQueue};
+use ::embedded_io::{ErrorKind, ErrorType, Read, ReadReady, Write, WriteReady};
+
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> ErrorType for StorageQueue<u8, S, I, P> {
+    type Error = ErrorKind;
+}
+
+/// Reads the oldest bytes. An empty queue reads as end-of-file until more bytes are written.
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> Read for StorageQueue<u8, S, I, P> {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, ErrorKind> {
+        Ok(self.dequeue_into(buf))
+    }
+}
+
+/// Writes as many bytes as fit. A full queue fails with [`ErrorKind::WriteZero`] instead of
+/// blocking.
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> Write for StorageQueue<u8, S, I, P> {
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> Result<usize, ErrorKind> {
+        match self.enqueue_slice(buf) {
+            0 if !buf.is_empty() => Err(ErrorKind::WriteZero),
+            written => Ok(written),
+        }
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
+
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> ReadReady for StorageQueue<u8, S, I, P> {
+    #[inline(always)]
+    fn read_ready(&mut self) -> Result<bool, ErrorKind> {
+        Ok(!self.is_empty())
+    }
+}
+
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> WriteReady for StorageQueue<u8, S, I, P> {
+    #[inline(always)]
+    fn write_ready(&mut self) -> Result<bool, ErrorKind> {
+        Ok(!self.is_full())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Queue, QueueRef};
+
+    #[test]
+    fn test_pipe_bytes_through() {
+        let mut queue: Queue<u8, 8> = Queue::new();
+        queue.write_all(b"hello").unwrap();
+        assert_eq!(queue.write(b"world"), Ok(3));
+        assert_eq!(queue.write(b"!"), Err(ErrorKind::WriteZero));
+        assert_eq!(queue.write(b""), Ok(0));
+        assert!(!queue.write_ready().unwrap());
+
+        let mut buf = [0; 6];
+        assert_eq!(queue.read(&mut buf), Ok(6));
+        assert_eq!(&buf, b"hellow");
+        queue.write_all(b"!!").unwrap();
+        let mut rest = [0; 4];
+        queue.read_exact(&mut rest).unwrap();
+        assert_eq!(&rest, b"or!!");
+        assert!(!queue.read_ready().unwrap());
+        assert_eq!(queue.read(&mut buf), Ok(0));
+    }
+
+    #[test]
+    fn test_borrowed_buffer() {
+        let mut arena = [const { core::mem::MaybeUninit::uninit() }; 4];
+        let mut queue = QueueRef::new(&mut arena[..]);
+        assert_eq!(queue.write(b"hello"), Ok(4));
+        let mut out = [0; 8];
+        assert_eq!(queue.read(&mut out), Ok(4));
+        assert_eq!(out[..4], *b"hell");
+    }
+}
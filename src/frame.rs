@@ -0,0 +1,138 @@
+use crate::Queue;
+
+/// Bytes in front of every frame, holding its length as a little-endian `u16`.
+const PREFIX: usize = 2;
+
+/// FIFO queue of variable-sized byte frames packed into one ring of `BYTES` bytes. Each frame
+/// costs its length plus a 2-byte length prefix.
+pub struct FrameQueue<const BYTES: usize> {
+    bytes: Queue<u8, BYTES>,
+    frames: usize,
+}
+
+impl<const BYTES: usize> FrameQueue<BYTES> {
+    /// Number of bytes the ring can hold, including length prefixes.
+    pub const CAPACITY: usize = BYTES;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            bytes: Queue::new(),
+            frames: 0,
+        }
+    }
+
+    /// Enqueues `frame` whole, or returns `false` if it does not fit.
+    #[inline(always)]
+    pub fn enqueue_frame(&mut self, frame: &[u8]) -> bool {
+        let Ok(len) = u16::try_from(frame.len()) else {
+            return false;
+        };
+        if PREFIX + frame.len() > self.bytes.remaining_capacity() {
+            return false;
+        }
+        self.bytes.enqueue_slice(&len.to_le_bytes());
+        self.bytes.enqueue_slice(frame);
+        self.frames += 1;
+        true
+    }
+
+    /// Dequeues the oldest frame into `out` and returns its length. If `out` is shorter, only
+    /// the leading bytes are copied and the rest of the frame is discarded.
+    #[inline(always)]
+    pub fn dequeue_frame(&mut self, out: &mut [u8]) -> Option<usize> {
+        let len = self.next_frame_len()?;
+        self.bytes.advance(PREFIX);
+        let copied = len.min(out.len());
+        self.bytes.dequeue_into(&mut out[..copied]);
+        self.bytes.advance(len - copied);
+        self.frames -= 1;
+        Some(len)
+    }
+
+    /// Length of the oldest frame, to size the buffer passed to [`FrameQueue::dequeue_frame`].
+    #[inline(always)]
+    pub fn next_frame_len(&self) -> Option<usize> {
+        let low = *self.bytes.get(0)?;
+        let high = *self.bytes.get(1)?;
+        Some(u16::from_le_bytes([low, high]) as usize)
+    }
+
+    /// Number of frames queued.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.frames
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// Bytes left for new frames, including their length prefixes.
+    #[inline(always)]
+    pub fn remaining_bytes(&self) -> usize {
+        self.bytes.remaining_capacity()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.frames = 0;
+    }
+}
+
+impl<const BYTES: usize> Default for FrameQueue<BYTES> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variable_sized_frames() {
+        let mut queue: FrameQueue<16> = FrameQueue::new();
+        assert!(queue.enqueue_frame(b"abcd"));
+        assert!(queue.enqueue_frame(b""));
+        assert!(queue.enqueue_frame(b"efghij"));
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.remaining_bytes(), 0);
+        assert!(!queue.enqueue_frame(b""));
+
+        let mut out = [0; 8];
+        assert_eq!(queue.dequeue_frame(&mut out), Some(4));
+        assert_eq!(&out[..4], b"abcd");
+        assert_eq!(queue.dequeue_frame(&mut out), Some(0));
+        assert!(queue.enqueue_frame(b"klmn"));
+        assert_eq!(queue.dequeue_frame(&mut out), Some(6));
+        assert_eq!(&out[..6], b"efghij");
+        assert_eq!(queue.dequeue_frame(&mut out), Some(4));
+        assert_eq!(&out[..4], b"klmn");
+        assert_eq!(queue.dequeue_frame(&mut out), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_short_buffer_discards_rest() {
+        let mut queue: FrameQueue<16> = FrameQueue::new();
+        assert!(!queue.enqueue_frame(&[0; 15]));
+        assert!(queue.enqueue_frame(b"abcdef"));
+        assert!(queue.enqueue_frame(b"gh"));
+        assert_eq!(queue.next_frame_len(), Some(6));
+
+        let mut out = [0; 3];
+        assert_eq!(queue.dequeue_frame(&mut out), Some(6));
+        assert_eq!(&out, b"abc");
+        assert_eq!(queue.dequeue_frame(&mut out), Some(2));
+        assert_eq!(&out[..2], b"gh");
+    }
+}
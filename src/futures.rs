@@ -0,0 +1,195 @@
+use crate::{CsQueue, QueueFull};
+use ::futures_core::Stream;
+use ::futures_sink::Sink;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+/// Yields elements as they are enqueued. The stream never ends, since any context may enqueue
+/// again later. The queue keeps a single waker for its stream, so only one task should poll it
+/// at a time; when two do, only the one that polled last is woken.
+impl<T, const N: usize> Stream for &CsQueue<T, N> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.poll_dequeue(cx).map(Some)
+    }
+}
+
+/// Ready whenever the queue has a free slot, like a bounded channel. `start_send` hands the
+/// item back if another context took that slot in the meantime. An item is visible to consumers
+/// as soon as it is sent, so flushing and closing never wait. As with the stream, only the task
+/// that polled last is woken, so one task at a time should send.
+impl<T, const N: usize> Sink<T> for &CsQueue<T, N> {
+    type Error = QueueFull<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.poll_free_slot(cx).map(Ok)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.try_enqueue(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use ::futures::executor::block_on;
+    use ::futures::{SinkExt, StreamExt};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Wake, Waker};
+    use std::thread;
+    use std::vec::Vec;
+
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn counting_wakers() -> ([Arc<CountingWaker>; 2], [Waker; 2]) {
+        let counters = [(); 2].map(|_| Arc::new(CountingWaker(AtomicUsize::new(0))));
+        let wakers = counters.clone().map(Waker::from);
+        (counters, wakers)
+    }
+
+    fn woken(counters: &[Arc<CountingWaker>; 2]) -> [usize; 2] {
+        counters
+            .each_ref()
+            .map(|counter| counter.0.load(Ordering::Relaxed))
+    }
+
+    #[test]
+    fn test_stream_ready_chunks() {
+        static QUEUE: CsQueue<u32, 4> = CsQueue::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                block_on(async {
+                    for i in 0..100 {
+                        QUEUE.enqueue_async(i).await;
+                    }
+                })
+            });
+            let received: Vec<u32> = block_on(
+                (&QUEUE)
+                    .ready_chunks(4)
+                    .flat_map(::futures::stream::iter)
+                    .take(100)
+                    .collect(),
+            );
+            assert_eq!(received, (0..100).collect::<Vec<_>>());
+        });
+    }
+
+    #[test]
+    fn test_while_let_next() {
+        let queue: CsQueue<u32, 4> = CsQueue::new();
+        queue.try_enqueue(1).unwrap();
+        queue.try_enqueue(2).unwrap();
+        let mut stream = &queue;
+        block_on(async {
+            assert_eq!(stream.next().await, Some(1));
+            assert_eq!(stream.next().await, Some(2));
+        });
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_sink_send_all() {
+        static QUEUE: CsQueue<u32, 4> = CsQueue::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                let mut sink = &QUEUE;
+                block_on(sink.send_all(&mut ::futures::stream::iter(0..100).map(Ok))).unwrap();
+            });
+            let received: Vec<u32> = block_on((&QUEUE).take(100).collect());
+            assert_eq!(received, (0..100).collect::<Vec<_>>());
+        });
+    }
+
+    #[test]
+    fn test_sink_waits_for_free_slot() {
+        let queue: CsQueue<u32, 1> = CsQueue::new();
+        let mut sink = &queue;
+        let waker = ::futures::task::noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut sink).poll_ready(&mut cx), Poll::Ready(Ok(())));
+        Pin::new(&mut sink).start_send(1).unwrap();
+        assert_eq!(Pin::new(&mut sink).poll_ready(&mut cx), Poll::Pending);
+        assert_eq!(Pin::new(&mut sink).poll_flush(&mut cx), Poll::Ready(Ok(())));
+        assert_eq!(Pin::new(&mut sink).start_send(2), Err(QueueFull(2)));
+        assert_eq!(queue.dequeue(), Some(1));
+        assert_eq!(Pin::new(&mut sink).poll_ready(&mut cx), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn test_send_filling_the_queue_completes() {
+        let queue: CsQueue<u32, 2> = CsQueue::new();
+        let mut sink = &queue;
+        block_on(async {
+            sink.send(1).await.unwrap();
+            sink.send(2).await.unwrap();
+            assert_eq!((&queue).take(2).collect::<Vec<_>>().await, [1, 2]);
+        });
+    }
+
+    #[test]
+    fn test_two_pending_streams_do_not_wake_each_other() {
+        let queue: CsQueue<u32, 2> = CsQueue::new();
+        let (counters, wakers) = counting_wakers();
+        let mut streams = [&queue, &queue];
+        let mut cx = [0, 1].map(|i| Context::from_waker(&wakers[i]));
+        for _ in 0..3 {
+            for i in 0..2 {
+                assert_eq!(
+                    Pin::new(&mut streams[i]).poll_next(&mut cx[i]),
+                    Poll::Pending
+                );
+            }
+        }
+        assert_eq!(woken(&counters), [0, 0]);
+        queue.try_enqueue(1).unwrap();
+        assert_eq!(woken(&counters), [0, 1]);
+        assert_eq!(
+            Pin::new(&mut streams[1]).poll_next(&mut cx[1]),
+            Poll::Ready(Some(1))
+        );
+    }
+
+    #[test]
+    fn test_two_pending_sinks_do_not_wake_each_other() {
+        let queue: CsQueue<u32, 1> = CsQueue::new();
+        queue.try_enqueue(0).unwrap();
+        let (counters, wakers) = counting_wakers();
+        let mut sinks = [&queue, &queue];
+        let mut cx = [0, 1].map(|i| Context::from_waker(&wakers[i]));
+        for _ in 0..3 {
+            for i in 0..2 {
+                assert_eq!(
+                    Pin::new(&mut sinks[i]).poll_ready(&mut cx[i]),
+                    Poll::Pending
+                );
+            }
+        }
+        assert_eq!(woken(&counters), [0, 0]);
+        assert_eq!(queue.dequeue(), Some(0));
+        assert_eq!(woken(&counters), [0, 1]);
+        assert_eq!(
+            Pin::new(&mut sinks[1]).poll_ready(&mut cx[1]),
+            Poll::Ready(Ok(()))
+        );
+    }
+}
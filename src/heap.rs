@@ -0,0 +1,214 @@
+use crate::QueueFull;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::slice;
+
+/// Decides which of two elements a [`PriorityQueue`] hands out first.
+pub trait Order {
+    /// Whether `a` comes out before `b`.
+    fn precedes<T: Ord>(a: &T, b: &T) -> bool;
+}
+
+/// Largest element first.
+pub struct Max;
+
+/// Smallest element first.
+pub struct Min;
+
+impl Order for Max {
+    #[inline(always)]
+    fn precedes<T: Ord>(a: &T, b: &T) -> bool {
+        a > b
+    }
+}
+
+impl Order for Min {
+    #[inline(always)]
+    fn precedes<T: Ord>(a: &T, b: &T) -> bool {
+        a < b
+    }
+}
+
+/// Statically allocated binary heap. Holds `N` elements and pops the largest first, or the
+/// smallest with `O` = [`Min`].
+pub struct PriorityQueue<T, const N: usize, O: Order = Max> {
+    buffer: [MaybeUninit<T>; N],
+    len: usize,
+    _order: PhantomData<O>,
+}
+
+impl<T, const N: usize, O: Order> PriorityQueue<T, N, O> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+            _order: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// The elements in heap order, which is not sorted.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buffer.as_ptr() as *const T, self.len) }
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        let len = self.len;
+        self.len = 0;
+        for item in &mut self.buffer[..len] {
+            unsafe { item.assume_init_drop() };
+        }
+    }
+}
+
+impl<T: Ord, const N: usize, O: Order> PriorityQueue<T, N, O> {
+    #[inline(always)]
+    pub fn push(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        if self.is_full() {
+            return Err(QueueFull(item));
+        }
+        self.buffer[self.len].write(item);
+        self.len += 1;
+        self.sift_up(self.len - 1);
+        Ok(())
+    }
+
+    /// Removes the element that comes out first.
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.len -= 1;
+        self.buffer.swap(0, self.len);
+        let item = unsafe { self.buffer[self.len].assume_init_read() };
+        self.sift_down(0);
+        Some(item)
+    }
+
+    /// Returns the element that comes out first.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    #[inline(always)]
+    fn precedes(&self, a: usize, b: usize) -> bool {
+        let items = self.as_slice();
+        O::precedes(&items[a], &items[b])
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.precedes(i, parent) {
+                break;
+            }
+            self.buffer.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let left = 2 * i + 1;
+            let right = left + 1;
+            let mut first = i;
+            if left < self.len && self.precedes(left, first) {
+                first = left;
+            }
+            if right < self.len && self.precedes(right, first) {
+                first = right;
+            }
+            if first == i {
+                break;
+            }
+            self.buffer.swap(i, first);
+            i = first;
+        }
+    }
+}
+
+impl<T, const N: usize, O: Order> Default for PriorityQueue<T, N, O> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, O: Order> Drop for PriorityQueue<T, N, O> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, O: Order> fmt::Debug for PriorityQueue<T, N, O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_pops_largest_first() {
+        let mut heap: PriorityQueue<u32, 8> = PriorityQueue::new();
+        assert_eq!(heap.pop(), None);
+        for item in [5, 1, 8, 3, 9, 2, 7, 4] {
+            assert_eq!(heap.push(item), Ok(()));
+        }
+        assert_eq!(heap.push(6), Err(QueueFull(6)));
+        assert_eq!(heap.peek(), Some(&9));
+
+        let mut popped = [0; 8];
+        for slot in &mut popped {
+            *slot = heap.pop().unwrap();
+        }
+        assert_eq!(popped, [9, 8, 7, 5, 4, 3, 2, 1]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_min_order_and_drop() {
+        let mut heap: PriorityQueue<(u32, Rc<()>), 4, Min> = PriorityQueue::new();
+        let token = Rc::new(());
+        for deadline in [30, 10, 20] {
+            heap.push((deadline, token.clone())).unwrap();
+        }
+        assert_eq!(heap.pop().map(|(deadline, _)| deadline), Some(10));
+        assert_eq!(heap.peek().map(|(deadline, _)| *deadline), Some(20));
+        drop(heap);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{OwnedStorage, Policy, Queue, QueueFull, QueueIndex, Storage, StorageQueue};
+use ::heapless::{Deque, Vec};
+
+/// `heapless::Deque` names for the FIFO subset, so code can switch between the two types.
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Same as [`Self::try_enqueue`], handing back the bare item like `heapless` does.
+    #[inline(always)]
+    pub fn push_back(&mut self, item: T) -> Result<(), T> {
+        self.try_enqueue(item).map_err(|rejected| rejected.0)
+    }
+
+    #[inline(always)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.dequeue()
+    }
+
+    #[inline(always)]
+    pub fn front(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    #[inline(always)]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.peek_mut()
+    }
+}
+
+/// Works for any capacity `M`, handing the deque back if the queue has too few slots for its
+/// elements. Storage that can be allocated at any size gets exactly as many as there are.
+impl<T, S: OwnedStorage<T>, const M: usize, I: QueueIndex, P: Policy> TryFrom<Deque<T, M>>
+    for StorageQueue<T, S, I, P>
+{
+    type Error = QueueFull<Deque<T, M>>;
+
+    #[inline(always)]
+    fn try_from(deque: Deque<T, M>) -> Result<Self, Self::Error> {
+        if deque.len() > S::MAX_CAPACITY {
+            return Err(QueueFull(deque));
+        }
+        let mut queue = Self::from_storage(S::with_capacity(deque.len()));
+        queue.extend(deque);
+        Ok(queue)
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex, P: Policy> From<Queue<T, N, I, P>> for Deque<T, N> {
+    #[inline(always)]
+    fn from(queue: Queue<T, N, I, P>) -> Self {
+        let mut deque = Deque::new();
+        for item in queue {
+            let _ = deque.push_back(item);
+        }
+        deque
+    }
+}
+
+/// The first element of `vec` ends up at the front. Works for any capacity `M`, like the
+/// conversion from a deque.
+impl<T, S: OwnedStorage<T>, const M: usize, I: QueueIndex, P: Policy> TryFrom<Vec<T, M>>
+    for StorageQueue<T, S, I, P>
+{
+    type Error = QueueFull<Vec<T, M>>;
+
+    #[inline(always)]
+    fn try_from(vec: Vec<T, M>) -> Result<Self, Self::Error> {
+        if vec.len() > S::MAX_CAPACITY {
+            return Err(QueueFull(vec));
+        }
+        let mut queue = Self::from_storage(S::with_capacity(vec.len()));
+        queue.extend(vec);
+        Ok(queue)
+    }
+}
+
+/// The front element ends up first.
+impl<T, const N: usize, I: QueueIndex, P: Policy> From<Queue<T, N, I, P>> for Vec<T, N> {
+    #[inline(always)]
+    fn from(queue: Queue<T, N, I, P>) -> Self {
+        let mut vec = Vec::new();
+        for item in queue {
+            let _ = vec.push(item);
+        }
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_through_heapless() {
+        let mut queue: Queue<u32, 4> = Queue::from_array([26769, 64004, 63460]);
+        assert_eq!(queue.pop_front(), Some(26769));
+        assert_eq!(queue.push_back(857), Ok(()));
+        assert_eq!(queue.push_back(4711), Ok(()));
+        assert_eq!(queue.push_back(1), Err(1));
+
+        let deque: Deque<u32, 4> = queue.into();
+        assert!(deque.iter().copied().eq([64004, 63460, 857, 4711]));
+        let queue: Queue<u32, 4, u8> = Queue::try_from(deque).unwrap();
+        let vec: Vec<u32, 4> = queue.into();
+        assert_eq!(vec, [64004, 63460, 857, 4711]);
+        let mut queue: Queue<u32, 4> = Queue::try_from(vec).unwrap();
+        assert_eq!(queue.front(), Some(&64004));
+        *queue.front_mut().unwrap() = 2;
+        assert_eq!(queue.len(), 4);
+    }
+
+    #[test]
+    fn test_try_from_other_capacity() {
+        let vec: Vec<u32, 8> = Vec::from_slice(&[1, 2, 3]).unwrap();
+        let queue: Queue<u32, 3> = Queue::try_from(vec).unwrap();
+        assert!(queue.iter().copied().eq([1, 2, 3]));
+
+        let vec: Vec<u32, 8> = Vec::from_slice(&[1, 2, 3, 4, 5]).unwrap();
+        let Err(QueueFull(vec)) = Queue::<u32, 4>::try_from(vec) else {
+            panic!("five elements do not fit in four slots");
+        };
+        assert_eq!(vec, [1, 2, 3, 4, 5]);
+        let mut deque: Deque<u32, 8> = Deque::new();
+        deque.extend([1, 2, 3, 4, 5]);
+        let Err(QueueFull(deque)) = Queue::<u32, 4>::try_from(deque) else {
+            panic!("five elements do not fit in four slots");
+        };
+        assert_eq!(deque.len(), 5);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_try_from_into_boxed_queue() {
+        let vec: Vec<u32, 8> = Vec::from_slice(&[1, 2, 3, 4, 5]).unwrap();
+        let queue: crate::BoxedQueue<u32> = crate::BoxedQueue::try_from(vec).unwrap();
+        assert_eq!(queue.capacity(), 5);
+        assert!(queue.iter().copied().eq([1, 2, 3, 4, 5]));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{Iter, Queue, QueueIndex};
+use core::fmt;
+
+/// Keeps the last `N` values written: every write is accepted, evicting the oldest value once
+/// the buffer is full.
+pub struct HistoryBuffer<T, const N: usize, I: QueueIndex = usize> {
+    ring: Queue<T, N, I>,
+}
+
+impl<T, const N: usize, I: QueueIndex> HistoryBuffer<T, N, I> {
+    /// Number of values the buffer keeps.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { ring: Queue::new() }
+    }
+
+    /// Records `item`, returning the value it evicted, if any.
+    #[inline(always)]
+    pub fn write(&mut self, item: T) -> Option<T> {
+        self.ring.enqueue_overwrite(item)
+    }
+
+    /// The most recently written value.
+    #[inline(always)]
+    pub fn recent(&self) -> Option<&T> {
+        self.ring.back()
+    }
+
+    /// The oldest value still kept.
+    #[inline(always)]
+    pub fn oldest(&self) -> Option<&T> {
+        self.ring.peek()
+    }
+
+    /// Iterates from oldest to most recent; `.rev()` gives the most recent first.
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.ring.iter()
+    }
+
+    #[inline(always)]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.ring.as_slices()
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ring.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex> Default for HistoryBuffer<T, N, I> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize, I: QueueIndex> Clone for HistoryBuffer<T, N, I> {
+    fn clone(&self) -> Self {
+        Self {
+            ring: self.ring.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, I: QueueIndex> fmt::Debug for HistoryBuffer<T, N, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Writes every item, so only the last `N` are kept.
+impl<T, const N: usize, I: QueueIndex> Extend<T> for HistoryBuffer<T, N, I> {
+    #[inline(always)]
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        for item in iter {
+            self.write(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_last_n() {
+        let mut history: HistoryBuffer<u32, 3> = HistoryBuffer::new();
+        assert_eq!(history.recent(), None);
+        assert_eq!(history.write(1), None);
+        assert_eq!(history.write(2), None);
+        assert_eq!(history.recent(), Some(&2));
+        assert_eq!(history.oldest(), Some(&1));
+
+        history.extend(3..=5);
+        assert_eq!(history.write(6), Some(3));
+        assert_eq!(history.recent(), Some(&6));
+        assert_eq!(history.oldest(), Some(&4));
+        assert!(history.iter().copied().eq([4, 5, 6]));
+        assert!(history.iter().rev().copied().eq([6, 5, 4]));
+        assert_eq!(history.iter().sum::<u32>() / history.len() as u32, 5);
+    }
+}
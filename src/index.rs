@@ -0,0 +1,43 @@
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Unsigned integer type a [`Queue`](crate::Queue) stores its head and length in. Picking the
+/// smallest type that can hold `N` keeps the struct overhead down for small queues.
+pub const trait QueueIndex: Copy + sealed::Sealed {
+    /// Largest capacity representable by this type.
+    const MAX: usize;
+
+    const ZERO: Self;
+
+    fn to_usize(self) -> usize;
+
+    /// Truncating conversion; callers never pass values above [`Self::MAX`].
+    fn from_usize(value: usize) -> Self;
+}
+
+macro_rules! impl_queue_index {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl const QueueIndex for $ty {
+                const MAX: usize = if <$ty>::BITS < usize::BITS { <$ty>::MAX as usize } else { usize::MAX };
+
+                const ZERO: Self = 0;
+
+                #[inline(always)]
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                #[inline(always)]
+                fn from_usize(value: usize) -> Self {
+                    value as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_queue_index!(u8, u16, u32, usize);
@@ -0,0 +1,277 @@
+use crate::{wrap_at, OwnedStorage, Policy, QueueIndex, Reject, Storage, StorageQueue};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+/// Iterator over references to the queued elements, from oldest to newest.
+pub struct Iter<'a, T> {
+    buffer: &'a [MaybeUninit<T>],
+    head: usize,
+    len: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    #[inline(always)]
+    pub(crate) fn new<S: Storage<T>, I: QueueIndex, P: Policy>(
+        queue: &'a StorageQueue<T, S, I, P>,
+    ) -> Self {
+        Self {
+            buffer: queue.slots(),
+            head: queue.head(),
+            len: queue.len(),
+        }
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let head = self.head;
+        self.head = wrap_at(self.head + 1, self.buffer.len());
+        self.len -= 1;
+        Some(unsafe { self.buffer[head].assume_init_ref() })
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let last = wrap_at(self.head + self.len, self.buffer.len());
+        Some(unsafe { self.buffer[last].assume_init_ref() })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<'a, T, S: Storage<T>, I: QueueIndex, P: Policy> IntoIterator for &'a StorageQueue<T, S, I, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over mutable references to the queued elements, from oldest to newest.
+pub struct IterMut<'a, T> {
+    buffer: *mut MaybeUninit<T>,
+    capacity: usize,
+    head: usize,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    #[inline(always)]
+    pub(crate) fn new<S: Storage<T>, I: QueueIndex, P: Policy>(
+        queue: &'a mut StorageQueue<T, S, I, P>,
+    ) -> Self {
+        let (head, len) = (queue.head(), queue.len());
+        let slots = queue.slots_mut();
+        Self {
+            buffer: slots.as_mut_ptr(),
+            capacity: slots.len(),
+            head,
+            len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let head = self.head;
+        self.head = wrap_at(self.head + 1, self.capacity);
+        self.len -= 1;
+        // Each live slot is yielded at most once, so the returned references never alias.
+        Some(unsafe { (*self.buffer.add(head)).assume_init_mut() })
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IterMut<'_, T> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let last = wrap_at(self.head + self.len, self.capacity);
+        Some(unsafe { (*self.buffer.add(last)).assume_init_mut() })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<'a, T, S: Storage<T>, I: QueueIndex, P: Policy> IntoIterator
+    for &'a mut StorageQueue<T, S, I, P>
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Owning iterator that dequeues elements from oldest to newest.
+pub struct IntoIter<T, S: Storage<T>, I: QueueIndex = usize, P: Policy = Reject> {
+    queue: StorageQueue<T, S, I, P>,
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Iterator for IntoIter<T, S, I, P> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.dequeue()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queue.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> DoubleEndedIterator for IntoIter<T, S, I, P> {
+    #[inline(always)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.queue.take_back()
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> ExactSizeIterator for IntoIter<T, S, I, P> {}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> FusedIterator for IntoIter<T, S, I, P> {}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> IntoIterator for StorageQueue<T, S, I, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, S, I, P>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { queue: self }
+    }
+}
+
+/// Draining iterator that dequeues elements from oldest to newest. Any elements not yielded are
+/// discarded when the iterator is dropped.
+pub struct Drain<'a, T, S: Storage<T>, I: QueueIndex = usize, P: Policy = Reject> {
+    queue: &'a mut StorageQueue<T, S, I, P>,
+}
+
+impl<'a, T, S: Storage<T>, I: QueueIndex, P: Policy> Drain<'a, T, S, I, P> {
+    #[inline(always)]
+    pub(crate) fn new(queue: &'a mut StorageQueue<T, S, I, P>) -> Self {
+        Self { queue }
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Iterator for Drain<'_, T, S, I, P> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.dequeue()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queue.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> ExactSizeIterator for Drain<'_, T, S, I, P> {}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> FusedIterator for Drain<'_, T, S, I, P> {}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Drop for Drain<'_, T, S, I, P> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.queue.clear();
+    }
+}
+
+/// Iterator that dequeues batches of up to `size` elements, each moved into a queue of its own
+/// with block copies. Elements not yet yielded stay in the queue when the iterator is dropped.
+pub struct Chunks<'a, T, S: OwnedStorage<T>, I: QueueIndex = usize, P: Policy = Reject> {
+    queue: &'a mut StorageQueue<T, S, I, P>,
+    size: usize,
+}
+
+impl<'a, T, S: OwnedStorage<T>, I: QueueIndex, P: Policy> Chunks<'a, T, S, I, P> {
+    #[inline(always)]
+    pub(crate) fn new(queue: &'a mut StorageQueue<T, S, I, P>, size: usize) -> Self {
+        Self { queue, size }
+    }
+}
+
+impl<T, S: OwnedStorage<T>, I: QueueIndex, P: Policy> Iterator for Chunks<'_, T, S, I, P> {
+    type Item = StorageQueue<T, S, I, P>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.queue.is_empty() || self.size == 0 {
+            return None;
+        }
+        let mut batch = StorageQueue::from_storage(self.queue.buffer.new_like());
+        self.queue.transfer(&mut batch, self.size);
+        Some(batch)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.size {
+            0 => 0,
+            size => self.queue.len().div_ceil(size),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T, S: OwnedStorage<T>, I: QueueIndex, P: Policy> ExactSizeIterator for Chunks<'_, T, S, I, P> {}
+
+impl<T, S: OwnedStorage<T>, I: QueueIndex, P: Policy> FusedIterator for Chunks<'_, T, S, I, P> {}
@@ -0,0 +1,263 @@
+use crate::QueueFull;
+use core::fmt;
+use core::mem::MaybeUninit;
+
+/// How [`LaneQueue::dequeue`] picks the lane to take an element from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaneSelect {
+    /// The first non-empty lane, so lane 0 has the highest priority.
+    Priority,
+    /// The first non-empty lane after the one that was dequeued from last.
+    RoundRobin,
+    /// Only the given lane.
+    Lane(usize),
+}
+
+/// A lane's share of the buffer, with its own ring inside.
+#[derive(Clone, Copy)]
+struct Lane {
+    start: usize,
+    capacity: usize,
+    head: usize,
+    len: usize,
+}
+
+impl Lane {
+    /// Buffer index of the `n`-th oldest element.
+    #[inline(always)]
+    fn slot(&self, n: usize) -> usize {
+        let offset = self.head + n;
+        self.start
+            + if offset >= self.capacity {
+                offset - self.capacity
+            } else {
+                offset
+            }
+    }
+}
+
+/// `LANES` independent FIFO lanes sharing one buffer of `N` elements, each with its own
+/// capacity, e.g. a small control lane next to a large bulk lane. Lanes are numbered from 0
+/// and methods taking a lane panic if it is out of range.
+pub struct LaneQueue<T, const N: usize, const LANES: usize> {
+    buffer: [MaybeUninit<T>; N],
+    lanes: [Lane; LANES],
+    /// Lane asked first by [`LaneSelect::RoundRobin`].
+    next: usize,
+}
+
+impl<T, const N: usize, const LANES: usize> LaneQueue<T, N, LANES> {
+    /// Gives lane `i` room for `capacities[i]` elements. Panics, or fails to compile in a
+    /// const context, if the capacities add up to more than `N`.
+    #[inline(always)]
+    pub const fn new(capacities: [usize; LANES]) -> Self {
+        let mut lanes = [Lane {
+            start: 0,
+            capacity: 0,
+            head: 0,
+            len: 0,
+        }; LANES];
+        let mut start = 0;
+        let mut i = 0;
+        while i < LANES {
+            lanes[i].start = start;
+            lanes[i].capacity = capacities[i];
+            start += capacities[i];
+            i += 1;
+        }
+        assert!(start <= N, "lane capacities exceed the buffer size N");
+        Self {
+            buffer: [const { MaybeUninit::uninit() }; N],
+            lanes,
+            next: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn try_enqueue(&mut self, lane: usize, item: T) -> Result<(), QueueFull<T>> {
+        let ring = &mut self.lanes[lane];
+        if ring.len == ring.capacity {
+            return Err(QueueFull(item));
+        }
+        let slot = ring.slot(ring.len);
+        ring.len += 1;
+        self.buffer[slot].write(item);
+        Ok(())
+    }
+
+    /// Dequeues the oldest element of the lane chosen by `select`, or returns `None` if that
+    /// finds nothing.
+    #[inline(always)]
+    pub fn dequeue(&mut self, select: LaneSelect) -> Option<T> {
+        self.dequeue_indexed(select).map(|(_, item)| item)
+    }
+
+    /// Like [`Self::dequeue`], also returning the lane the element came from.
+    pub fn dequeue_indexed(&mut self, select: LaneSelect) -> Option<(usize, T)> {
+        let lane = match select {
+            LaneSelect::Priority => (0..LANES).find(|&lane| self.lanes[lane].len > 0)?,
+            LaneSelect::RoundRobin => (0..LANES)
+                .map(|offset| (self.next + offset) % LANES)
+                .find(|&lane| self.lanes[lane].len > 0)?,
+            LaneSelect::Lane(lane) => lane,
+        };
+        let item = self.take_front(lane)?;
+        self.next = (lane + 1) % LANES;
+        Some((lane, item))
+    }
+
+    #[inline(always)]
+    fn take_front(&mut self, lane: usize) -> Option<T> {
+        let ring = &mut self.lanes[lane];
+        if ring.len == 0 {
+            return None;
+        }
+        let slot = ring.slot(0);
+        ring.head = if ring.head + 1 == ring.capacity {
+            0
+        } else {
+            ring.head + 1
+        };
+        ring.len -= 1;
+        Some(unsafe { self.buffer[slot].assume_init_read() })
+    }
+
+    /// The oldest element of `lane`.
+    #[inline(always)]
+    pub fn peek(&self, lane: usize) -> Option<&T> {
+        let ring = &self.lanes[lane];
+        if ring.len == 0 {
+            return None;
+        }
+        Some(unsafe { self.buffer[ring.slot(0)].assume_init_ref() })
+    }
+
+    /// The elements of `lane`, oldest first.
+    #[inline(always)]
+    pub fn iter(&self, lane: usize) -> impl Iterator<Item = &T> + '_ {
+        let ring = self.lanes[lane];
+        (0..ring.len).map(move |n| unsafe { self.buffer[ring.slot(n)].assume_init_ref() })
+    }
+
+    /// Number of elements in `lane`.
+    #[inline(always)]
+    pub fn lane_len(&self, lane: usize) -> usize {
+        self.lanes[lane].len
+    }
+
+    #[inline(always)]
+    pub fn lane_capacity(&self, lane: usize) -> usize {
+        self.lanes[lane].capacity
+    }
+
+    /// Drops the elements of `lane`.
+    pub fn clear_lane(&mut self, lane: usize) {
+        while self.take_front(lane).is_some() {}
+        self.lanes[lane].head = 0;
+    }
+
+    pub fn clear(&mut self) {
+        for lane in 0..LANES {
+            self.clear_lane(lane);
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of elements in all lanes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.lanes.iter().map(|ring| ring.len).sum()
+    }
+
+    #[inline(always)]
+    pub const fn lanes(&self) -> usize {
+        LANES
+    }
+}
+
+impl<T, const N: usize, const LANES: usize> Drop for LaneQueue<T, N, LANES> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, const LANES: usize> fmt::Debug for LaneQueue<T, N, LANES> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        for lane in 0..LANES {
+            list.entry(&fmt::from_fn(|f| {
+                f.debug_list().entries(self.iter(lane)).finish()
+            }));
+        }
+        list.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+    use std::rc::Rc;
+
+    const CONTROL: usize = 0;
+    const BULK: usize = 1;
+
+    #[test]
+    fn test_lanes_are_independent() {
+        let mut queue: LaneQueue<u32, 6, 2> = LaneQueue::new([2, 4]);
+        assert_eq!(queue.try_enqueue(CONTROL, 1), Ok(()));
+        assert_eq!(queue.try_enqueue(CONTROL, 2), Ok(()));
+        assert_eq!(queue.try_enqueue(CONTROL, 3), Err(QueueFull(3)));
+        for item in 10..14 {
+            assert_eq!(queue.try_enqueue(BULK, item), Ok(()));
+        }
+        assert_eq!(format!("{queue:?}"), "[[1, 2], [10, 11, 12, 13]]");
+
+        assert_eq!(
+            queue.dequeue_indexed(LaneSelect::Priority),
+            Some((CONTROL, 1))
+        );
+        assert_eq!(queue.dequeue(LaneSelect::RoundRobin), Some(10));
+        assert_eq!(queue.dequeue(LaneSelect::RoundRobin), Some(2));
+        assert_eq!(queue.dequeue(LaneSelect::Lane(CONTROL)), None);
+        assert_eq!(queue.try_enqueue(BULK, 14), Ok(()));
+        assert_eq!(queue.try_enqueue(CONTROL, 4), Ok(()));
+        assert_eq!(queue.dequeue(LaneSelect::RoundRobin), Some(11));
+        assert_eq!(queue.dequeue(LaneSelect::RoundRobin), Some(4));
+        assert_eq!(queue.peek(BULK), Some(&12));
+        assert_eq!((queue.lane_len(BULK), queue.len()), (3, 3));
+        assert!((0..3)
+            .map(|_| queue.dequeue(LaneSelect::Priority))
+            .eq([12, 13, 14].map(Some)));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_drop_releases_every_lane() {
+        let token = Rc::new(());
+        let mut queue: LaneQueue<Rc<()>, 5, 3> = LaneQueue::new([1, 0, 3]);
+        assert_eq!(queue.lane_capacity(1), 0);
+        assert!(queue.try_enqueue(1, token.clone()).is_err());
+        queue.try_enqueue(0, token.clone()).unwrap();
+        for _ in 0..3 {
+            queue.try_enqueue(2, token.clone()).unwrap();
+        }
+        queue.clear_lane(0);
+        assert_eq!(Rc::strong_count(&token), 4);
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    #[should_panic = "lane capacities exceed the buffer size N"]
+    fn test_capacities_must_fit() {
+        let _ = LaneQueue::<u8, 4, 2>::new([3, 2]);
+    }
+}
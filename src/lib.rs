@@ -1,116 +1,2437 @@
-//! This is a statically allocated FIFO Queue for copy types. Holds `N`-1 elements.
-//! `N` must be a power of 2, i.e. 8, 16, 32, 64.
+//! This is a statically allocated FIFO Queue. Holds `N` elements.
+//! Any `N` works, but powers of 2, i.e. 8, 16, 32, 64, wrap with a bitmask and are fastest.
 #![no_std]
 #![allow(incomplete_features)]
+#![feature(const_trait_impl)]
 #![feature(generic_const_exprs)]
-use core::mem::MaybeUninit;
+#![feature(const_destruct)]
+#![cfg_attr(feature = "zeroize", feature(core_intrinsics, const_eval_select))]
+#![cfg_attr(feature = "zeroize", allow(internal_features))]
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-pub struct Queue<T: Copy, const N: usize> {
-    buffer: [MaybeUninit<T>; N],
-    head: usize,
-    tail: usize,
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::{Destruct, PhantomData};
+use core::mem::{self, MaybeUninit};
+use core::ops::{Index, IndexMut};
+use core::str::{self, Utf8Error};
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+mod bip;
+#[cfg(feature = "alloc")]
+mod boxed;
+mod cobs;
+#[cfg(feature = "critical-section")]
+mod cs;
+#[cfg(feature = "defmt")]
+mod defmt;
+mod deque;
+#[cfg(feature = "embedded-io")]
+mod embedded_io;
+mod frame;
+#[cfg(feature = "futures")]
+mod futures;
+mod heap;
+#[cfg(feature = "heapless")]
+mod heapless;
+mod history;
+mod index;
+mod iter;
+mod lane;
+#[cfg(feature = "log")]
+mod log;
+mod lossy;
+mod mpmc;
+mod mpsc;
+#[cfg(feature = "nb")]
+mod nb;
+mod oneshot;
+mod persistent;
+mod ping_pong;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod policy;
+mod prio;
+mod queue_ref;
+#[cfg(feature = "rkyv")]
+mod rkyv;
+mod round_robin;
+#[cfg(feature = "serde")]
+mod serde;
+mod snapshot;
+mod spsc;
+mod stack;
+#[cfg(feature = "stats")]
+mod stats;
+mod storage;
+mod timed;
+mod validate;
+#[cfg(feature = "critical-section")]
+mod waiter;
+#[cfg(feature = "critical-section")]
+mod watch;
+#[cfg(feature = "bytemuck")]
+mod wire;
+
+pub use bip::{BipBuffer, BipConsumer, BipProducer, ReadGrant, WriteGrant};
+#[cfg(feature = "alloc")]
+pub use boxed::BoxedQueue;
+#[cfg(feature = "critical-section")]
+pub use cs::CsQueue;
+pub use deque::Deque;
+pub use frame::FrameQueue;
+pub use heap::{Max, Min, Order, PriorityQueue};
+pub use history::HistoryBuffer;
+pub use index::QueueIndex;
+pub use iter::{Chunks, Drain, IntoIter, Iter, IterMut};
+pub use lane::{LaneQueue, LaneSelect};
+#[cfg(feature = "log")]
+pub use log::LogQueue;
+pub use lossy::LossyQueue;
+pub use mpmc::MpmcQueue;
+pub use mpsc::{MpscConsumer, MpscQueue};
+pub use oneshot::OneShot;
+pub use persistent::PersistentQueue;
+pub use ping_pong::PingPong;
+#[cfg(feature = "bytemuck")]
+pub use pod::PodQueue;
+pub use policy::{OnFull, Overwrite, Panic, Policy, Reject};
+pub use prio::PrioQueue;
+pub use queue_ref::QueueRef;
+pub use round_robin::{RoundRobin, Source};
+pub use snapshot::QueueSnapshot;
+pub use spsc::{Consumer, Producer, SpscQueue};
+pub use stack::Stack;
+#[cfg(feature = "stats")]
+pub use stats::{Stats, OCCUPANCY_BUCKETS};
+pub use storage::{OwnedStorage, Storage};
+pub use timed::TimedQueue;
+pub use validate::Corruption;
+#[cfg(feature = "critical-section")]
+pub use watch::Watch;
+#[cfg(feature = "bytemuck")]
+pub use wire::WireError;
+
+/// Targets without native compare-and-swap, e.g. thumbv6m, get it from `portable_atomic`.
+#[cfg(feature = "portable-atomic")]
+use ::portable_atomic as atomic;
+#[cfg(not(feature = "portable-atomic"))]
+use core::sync::atomic;
+
+/// Wraps a position in `0..2 * capacity` back into the buffer. Callers never pass anything
+/// larger, so for sizes other than powers of 2 a single compare replaces the division a `%`
+/// would cost.
+#[inline(always)]
+pub(crate) const fn wrap_at(i: usize, capacity: usize) -> usize {
+    if capacity.is_power_of_two() {
+        i & (capacity - 1)
+    } else if i >= capacity {
+        i - capacity
+    } else {
+        i
+    }
+}
+
+/// [`wrap_at`] for a capacity known at compile time.
+#[inline(always)]
+pub(crate) const fn wrap<const N: usize>(i: usize) -> usize {
+    wrap_at(i, N)
+}
+
+/// Zeroes a vacated slot from a `const fn`. At run time this is [`Zeroize`], whose volatile
+/// writes cannot be optimized away; during constant evaluation there is nothing to scrub, so a
+/// plain store does.
+#[cfg(feature = "zeroize")]
+#[inline(always)]
+const fn wipe<T>(slot: &mut MaybeUninit<T>) {
+    const fn at_compile_time<T>(slot: &mut MaybeUninit<T>) {
+        unsafe { core::ptr::write_bytes(slot.as_mut_ptr(), 0, 1) };
+    }
+    fn at_run_time<T>(slot: &mut MaybeUninit<T>) {
+        slot.zeroize();
+    }
+    core::intrinsics::const_eval_select((slot,), at_compile_time, at_run_time)
+}
+
+/// Returned by [`Queue::try_enqueue`] when the queue is full, handing back the rejected item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFull<T>(pub T);
+
+impl<T> fmt::Display for QueueFull<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("queue is full")
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for QueueFull<T> {}
+
+/// Returned by [`Queue::try_dequeue`] when the queue is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueEmpty;
+
+impl fmt::Display for QueueEmpty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("queue is empty")
+    }
 }
 
-impl<T: Copy, const N: usize> Queue<T, N> {
+impl core::error::Error for QueueEmpty {}
+
+/// FIFO queue over any [`Storage`], holding as many elements as the storage has slots. All
+/// queue types share this implementation: [`Queue`] keeps its slots inline, [`QueueRef`] in
+/// borrowed memory and, with the `alloc` feature, [`BoxedQueue`] on the heap.
+///
+/// The optional `I` parameter selects the integer type used for the head and length; it must
+/// be able to hold the capacity. The optional `P` parameter selects what
+/// [`Self::enqueue`] does when the queue is full, see [`Policy`].
+pub struct StorageQueue<T, S: Storage<T>, I: QueueIndex = usize, P: Policy = Reject> {
+    buffer: S,
+    head: I,
+    len: I,
+    #[cfg(feature = "stats")]
+    stats: Stats,
+    _item: PhantomData<T>,
+    _policy: PhantomData<P>,
+}
+
+/// Statically allocated FIFO queue holding `N` elements inline, e.g. `Queue<u8, 16, u8>`.
+/// Invalid capacities, 0 or more than `I` can count, fail to compile.
+///
+/// Zero-sized element types are supported: `Queue<(), N>` stores nothing but its head and
+/// length, so it counts up to `N` tokens, e.g. events handed from an interrupt to the main
+/// loop.
+pub type Queue<T, const N: usize, I = usize, P = Reject> =
+    StorageQueue<T, [MaybeUninit<T>; N], I, P>;
+
+impl<T, const N: usize, I: QueueIndex, P: Policy> Queue<T, N, I, P> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    /// Evaluated by [`Self::new`] so that invalid capacities fail to compile.
+    const VALID: () = {
+        assert!(N > 0, "queue capacity N must be at least 1");
+        assert!(
+            N <= I::MAX,
+            "queue capacity N does not fit in the index type"
+        );
+    };
+
     #[inline(always)]
     pub const fn new() -> Self {
+        let () = Self::VALID;
         Self {
             buffer: [const { MaybeUninit::uninit() }; N],
-            head: 0,
-            tail: 0,
+            head: I::ZERO,
+            len: I::ZERO,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+            _item: PhantomData,
+            _policy: PhantomData,
+        }
+    }
+
+    /// Creates a queue holding the elements of `init`, with `init[0]` at the front.
+    /// Fails to compile if `M` exceeds [`Self::CAPACITY`].
+    #[inline(always)]
+    pub const fn from_array<const M: usize>(init: [T; M]) -> Self
+    where
+        I: [const] QueueIndex,
+    {
+        let () = Self::assert_fits::<M>();
+        let mut queue = Self::new();
+        // Elements are moved out one by one, so `init` must not be dropped afterwards.
+        let init = MaybeUninit::new(init);
+        let src = init.as_ptr() as *const T;
+        let mut i = 0;
+        while i < M {
+            queue.buffer[i] = MaybeUninit::new(unsafe { src.add(i).read() });
+            i += 1;
+        }
+        queue.len = I::from_usize(M);
+        #[cfg(feature = "stats")]
+        {
+            queue.stats.high_water = M;
+        }
+        queue
+    }
+
+    #[inline(always)]
+    const fn assert_fits<const M: usize>() {
+        struct Fits<const M: usize, const N: usize>;
+        impl<const M: usize, const N: usize> Fits<M, N> {
+            const OK: () = assert!(M <= N, "array does not fit in the queue");
+        }
+        Fits::<M, N>::OK
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Takes over `buffer` as storage; whatever it holds is treated as free. Panics if the
+    /// storage has more slots than `I` can count.
+    #[inline(always)]
+    pub const fn from_storage(buffer: S) -> Self
+    where
+        S: [const] Storage<T>,
+    {
+        assert!(
+            buffer.as_slice().len() <= I::MAX,
+            "queue capacity does not fit in the index type"
+        );
+        Self {
+            buffer,
+            head: I::ZERO,
+            len: I::ZERO,
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+            _item: PhantomData,
+            _policy: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    const fn slots(&self) -> &[MaybeUninit<T>]
+    where
+        S: [const] Storage<T>,
+    {
+        self.buffer.as_slice()
+    }
+
+    #[inline(always)]
+    const fn slots_mut(&mut self) -> &mut [MaybeUninit<T>]
+    where
+        S: [const] Storage<T>,
+    {
+        self.buffer.as_mut_slice()
+    }
+
+    #[inline(always)]
+    const fn head(&self) -> usize
+    where
+        I: [const] QueueIndex,
+    {
+        self.head.to_usize()
+    }
+
+    /// Physical index of the `n`-th oldest element, for `n` up to the capacity.
+    #[inline(always)]
+    const fn slot(&self, n: usize) -> usize
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        wrap_at(self.head() + n, self.capacity())
+    }
+
+    /// Enqueues `item`. If the queue is full, the policy `P` decides: [`Reject`] discards
+    /// `item`, [`Overwrite`] evicts the oldest element, and [`Panic`] panics. Use
+    /// [`Self::try_enqueue`] to get a rejected item back instead. With the `checked` feature,
+    /// [`Reject`] panics too, to catch overflows where they happen.
+    ///
+    /// Usable in `const` initializers for element types whose drop can run at compile time,
+    /// e.g. any `Copy` type, since a rejected or evicted element is dropped.
+    #[inline(always)]
+    pub const fn enqueue(&mut self, item: T)
+    where
+        T: [const] Destruct,
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        match P::ON_FULL {
+            OnFull::Overwrite => {
+                drop(self.enqueue_overwrite(item));
+            }
+            OnFull::Panic if self.is_full() => panic!("queue is full"),
+            OnFull::Reject if cfg!(feature = "checked") && self.is_full() => {
+                panic!("enqueue on a full queue; use try_enqueue to handle a rejected item")
+            }
+            _ => {
+                let _ = self.try_enqueue(item);
+            }
+        }
+    }
+
+    /// Enqueues `item`, or hands it back if the queue is full. Like [`Self::dequeue`], it
+    /// compiles to straight-line code without a panic branch in optimized builds, so it is
+    /// safe to call from an interrupt handler.
+    #[inline(always)]
+    pub const fn try_enqueue(&mut self, item: T) -> Result<(), QueueFull<T>>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        if self.is_full() {
+            #[cfg(feature = "stats")]
+            self.stats.rejected(1);
+            return Err(QueueFull(item));
+        }
+        // The queue is not full, so `head + len` stays below twice the capacity and wraps into
+        // the buffer.
+        let tail = self.slot(self.len());
+        unsafe { (*self.slots_mut().as_mut_ptr().add(tail)).write(item) };
+        self.len = I::from_usize(self.len() + 1);
+        #[cfg(feature = "stats")]
+        self.stats.enqueued(1, self.len(), self.capacity());
+        Ok(())
+    }
+
+    /// Lets `f` construct the next element directly in its slot, so large elements are never
+    /// built on the stack and copied in. Returns `false` without calling `f` if the queue is full.
+    ///
+    /// # Safety
+    ///
+    /// `f` must fully initialize the slot it is given.
+    #[inline(always)]
+    pub unsafe fn enqueue_with(&mut self, f: impl FnOnce(&mut MaybeUninit<T>)) -> bool {
+        if self.is_full() {
+            #[cfg(feature = "stats")]
+            self.stats.rejected(1);
+            return false;
+        }
+        let slot = self.slot(self.len());
+        f(&mut self.slots_mut()[slot]);
+        self.len = I::from_usize(self.len() + 1);
+        #[cfg(feature = "stats")]
+        self.stats.enqueued(1, self.len(), self.capacity());
+        true
+    }
+
+    /// Returns the next free slot so an element can be built up across several calls before
+    /// being published with [`Self::commit`] or given up with [`Self::abort`]. Nothing is
+    /// reserved: staging again returns the same slot, and any other enqueue overwrites it. The
+    /// slot's contents are never dropped, so a fully built element that owns resources is
+    /// leaked if it is overwritten or aborted; drop it in place with
+    /// [`MaybeUninit::assume_init_drop`] first.
+    #[inline(always)]
+    pub fn stage(&mut self) -> Option<&mut MaybeUninit<T>> {
+        if self.is_full() {
+            return None;
+        }
+        let slot = self.slot(self.len());
+        Some(&mut self.slots_mut()[slot])
+    }
+
+    /// Publishes the slot returned by [`Self::stage`] as the newest element.
+    ///
+    /// # Safety
+    ///
+    /// The staged slot must have been fully initialized, with no other enqueue since it was
+    /// obtained.
+    #[inline(always)]
+    pub unsafe fn commit(&mut self) {
+        debug_assert!(!self.is_full());
+        self.len = I::from_usize(self.len() + 1);
+        #[cfg(feature = "stats")]
+        self.stats.enqueued(1, self.len(), self.capacity());
+    }
+
+    /// Gives up on the element being built in the slot returned by [`Self::stage`], leaving
+    /// the queue as it was. Whatever was written is not dropped, see [`Self::stage`]; with the
+    /// `zeroize` feature it is wiped.
+    #[inline(always)]
+    pub fn abort(&mut self) {
+        #[cfg(feature = "zeroize")]
+        if let Some(slot) = self.stage() {
+            slot.zeroize();
+        }
+    }
+
+    /// Returns the free slots as two slices, in the order they will be filled, so e.g. a DMA
+    /// receive can deposit data directly into the queue. Publish what arrived with
+    /// [`Self::assume_written`].
+    #[inline(always)]
+    pub fn free_slices_mut(&mut self) -> (&mut [MaybeUninit<T>], &mut [MaybeUninit<T>]) {
+        let (tail, free) = (self.slot(self.len()), self.remaining_capacity());
+        let first = free.min(self.capacity() - tail);
+        let (wrapped, back) = self.slots_mut().split_at_mut(tail);
+        (&mut back[..first], &mut wrapped[..free - first])
+    }
+
+    /// Publishes the first `n` slots returned by [`Self::free_slices_mut`] as the newest
+    /// elements.
+    ///
+    /// # Safety
+    ///
+    /// Those `n` slots must have been initialized, and `n` must not exceed
+    /// [`Self::remaining_capacity`].
+    #[inline(always)]
+    pub unsafe fn assume_written(&mut self, n: usize) {
+        debug_assert!(n <= self.remaining_capacity());
+        self.len = I::from_usize(self.len() + n);
+        #[cfg(feature = "stats")]
+        self.stats.enqueued(n, self.len(), self.capacity());
+    }
+
+    /// Enqueues `item`, evicting and returning the oldest element if the queue is full.
+    #[inline(always)]
+    pub const fn enqueue_overwrite(&mut self, item: T) -> Option<T>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        let evicted = if self.is_full() {
+            #[cfg(feature = "stats")]
+            self.stats.overwritten();
+            self.take_front()
+        } else {
+            None
+        };
+        // A slot was just freed, so this is always `Ok` and there is nothing to drop.
+        mem::forget(self.try_enqueue(item));
+        evicted
+    }
+
+    /// Enqueues as many leading elements of `items` as fit, returning how many were copied.
+    #[inline(always)]
+    pub fn enqueue_slice(&mut self, items: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let tail = self.slot(self.len());
+        let count = items.len().min(self.remaining_capacity());
+        let (first, second) = items[..count].split_at(count.min(self.capacity() - tail));
+        let slots = self.slots_mut();
+        slots[tail..tail + first.len()].write_copy_of_slice(first);
+        slots[..second.len()].write_copy_of_slice(second);
+        self.len = I::from_usize(self.len() + count);
+        #[cfg(feature = "stats")]
+        {
+            self.stats.enqueued(count, self.len(), self.capacity());
+            self.stats.rejected(items.len() - count);
+        }
+        count
+    }
+
+    /// Enqueues all of `items` or, if they do not all fit, none of them, handing the slice back.
+    #[inline(always)]
+    pub fn enqueue_all<'a>(&mut self, items: &'a [T]) -> Result<(), QueueFull<&'a [T]>>
+    where
+        T: Copy,
+    {
+        if items.len() > self.remaining_capacity() {
+            #[cfg(feature = "stats")]
+            self.stats.rejected(items.len());
+            return Err(QueueFull(items));
+        }
+        self.enqueue_slice(items);
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub const fn dequeue(&mut self) -> Option<T>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        self.take_front()
+    }
+
+    /// Dequeues exactly `K` elements, oldest first, or nothing if fewer than `K` are queued, so
+    /// fixed-size records are never torn.
+    #[inline(always)]
+    pub fn dequeue_exact<const K: usize>(&mut self) -> Option<[T; K]> {
+        if self.len() < K {
+            return None;
         }
+        // At least `K` elements are queued, so every call finds one.
+        Some(core::array::from_fn(|_| unsafe {
+            self.take_front().unwrap_unchecked()
+        }))
     }
 
+    /// Like [`Self::dequeue`], for callers that want to propagate an empty queue with `?`.
     #[inline(always)]
-    pub fn enqueue(&mut self, item: T) {
-        self.buffer[self.tail].write(item);
-        self.tail = (self.tail + 1) & (N - 1);
+    pub fn try_dequeue(&mut self) -> Result<T, QueueEmpty> {
+        self.dequeue().ok_or(QueueEmpty)
     }
 
     #[inline(always)]
-    pub fn dequeue(&mut self) -> Option<T> {
+    const fn take_front(&mut self) -> Option<T>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
         if self.is_empty() {
             return None;
         }
-        let head = self.head;
-        self.head = (self.head + 1) & (N - 1);
-        Some(unsafe { self.buffer[head].assume_init() })
+        let head = self.head();
+        self.head = I::from_usize(self.slot(1));
+        self.len = I::from_usize(self.len() - 1);
+        // The head always lies within the buffer, so the slot is read without a bounds check.
+        let front = unsafe { &mut *self.slots_mut().as_mut_ptr().add(head) };
+        let item = unsafe { front.assume_init_read() };
+        #[cfg(feature = "zeroize")]
+        wipe(front);
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(1);
+        Some(item)
     }
 
+    /// Removes the newest element, for the paths that pop from the back.
     #[inline(always)]
-    pub fn is_empty(&self) -> bool {
-        self.head == self.tail
+    pub(crate) const fn take_back(&mut self) -> Option<T>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        let len = self.len() - 1;
+        self.len = I::from_usize(len);
+        let last = self.slot(len);
+        let last = unsafe { &mut *self.slots_mut().as_mut_ptr().add(last) };
+        let item = unsafe { last.assume_init_read() };
+        #[cfg(feature = "zeroize")]
+        wipe(last);
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(1);
+        Some(item)
     }
-}
 
-impl<T: Copy, const N: usize> Default for Queue<T, N> {
+    /// Discards up to `n` of the oldest elements, returning how many were discarded. For
+    /// elements without drop glue this only moves the head.
     #[inline(always)]
-    fn default() -> Self {
-        Self::new()
+    pub fn advance(&mut self, n: usize) -> usize {
+        let count = n.min(self.len());
+        if mem::needs_drop::<T>() || cfg!(feature = "zeroize") {
+            for _ in 0..count {
+                self.take_front();
+            }
+        } else {
+            self.head = I::from_usize(self.slot(count));
+            self.len = I::from_usize(self.len() - count);
+            #[cfg(feature = "stats")]
+            self.stats.dequeued(count);
+        }
+        count
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns the longest run of the oldest elements that is contiguous in the buffer, e.g. to
+    /// hand the queue's own storage to a DMA transfer. Retire them with [`Self::release`] once
+    /// the transfer is complete.
+    #[inline(always)]
+    pub fn peek_contiguous(&self) -> &[T] {
+        self.as_slices().0
+    }
 
-    #[test]
-    fn test_it_works() {
-        let mut queue: Queue<u32, 4> = Queue::new();
-        assert!(queue.is_empty());
-        assert_eq!(queue.dequeue(), None);
+    /// Consumes up to `n` elements previously returned by [`Self::peek_contiguous`], returning
+    /// how many were consumed.
+    #[inline(always)]
+    pub fn release(&mut self, n: usize) -> usize {
+        self.advance(n)
+    }
 
-        queue.enqueue(4711);
-        queue.enqueue(7690);
-        assert_eq!(queue.dequeue(), Some(4711));
-        assert_eq!(queue.dequeue(), Some(7690));
-        assert_eq!(queue.dequeue(), None);
+    /// Dequeues up to `out.len()` elements into `out`, returning how many were copied.
+    #[inline(always)]
+    pub fn dequeue_into(&mut self, out: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let count = out.len().min(self.len());
+        let head = self.head();
+        let (first, second) = out[..count].split_at_mut(count.min(self.capacity() - head));
+        let slots = self.slots_mut();
+        first.copy_from_slice(unsafe { slots[head..head + first.len()].assume_init_ref() });
+        second.copy_from_slice(unsafe { slots[..second.len()].assume_init_ref() });
+        #[cfg(feature = "zeroize")]
+        {
+            slots[head..head + first.len()].zeroize();
+            slots[..second.len()].zeroize();
+        }
+        self.head = I::from_usize(self.slot(count));
+        self.len = I::from_usize(self.len() - count);
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(count);
+        count
+    }
 
-        queue.enqueue(34129);
-        queue.enqueue(33833);
-        assert_eq!(queue.dequeue(), Some(34129));
-        assert_eq!(queue.dequeue(), Some(33833));
-        assert_eq!(queue.dequeue(), None);
+    /// Moves up to `max` of the oldest elements to the back of `dst`, as many as fit, in at most
+    /// three block copies. Returns how many were moved.
+    #[inline(always)]
+    pub fn transfer<R: Storage<T>, J: QueueIndex, Q: Policy>(
+        &mut self,
+        dst: &mut StorageQueue<T, R, J, Q>,
+        max: usize,
+    ) -> usize {
+        let count = max.min(self.len()).min(dst.remaining_capacity());
+        let mut left = count;
+        while left > 0 {
+            let (head, tail) = (self.head(), dst.slot(dst.len()));
+            let chunk = left.min(self.capacity() - head).min(dst.capacity() - tail);
+            let src = self.slots()[head..head + chunk].as_ptr();
+            let dst_slots = dst.slots_mut()[tail..].as_mut_ptr();
+            unsafe { core::ptr::copy_nonoverlapping(src, dst_slots, chunk) };
+            #[cfg(feature = "zeroize")]
+            self.slots_mut()[head..head + chunk].zeroize();
+            self.head = I::from_usize(self.slot(chunk));
+            self.len = I::from_usize(self.len() - chunk);
+            dst.len = J::from_usize(dst.len() + chunk);
+            left -= chunk;
+        }
+        #[cfg(feature = "stats")]
+        {
+            self.stats.dequeued(count);
+            dst.stats.enqueued(count, dst.len(), dst.capacity());
+        }
+        count
+    }
 
-        queue.enqueue(41272);
-        queue.enqueue(26343);
-        assert_eq!(queue.dequeue(), Some(41272));
-        assert_eq!(queue.dequeue(), Some(26343));
-        assert_eq!(queue.dequeue(), None);
+    /// Moves as many elements of `other` to the back of this queue as fit, oldest first.
+    /// Returns how many were moved.
+    #[inline(always)]
+    pub fn append<R: Storage<T>, J: QueueIndex, Q: Policy>(
+        &mut self,
+        other: &mut StorageQueue<T, R, J, Q>,
+    ) -> usize {
+        other.transfer(self, usize::MAX)
+    }
 
-        queue.enqueue(45354);
-        assert_eq!(queue.dequeue(), Some(45354));
-        assert_eq!(queue.dequeue(), None);
+    /// Copies up to `out.len()` of the oldest elements into `out`, in order, without removing
+    /// them. Returns how many were copied.
+    #[inline(always)]
+    pub fn copy_to_slice(&self, out: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let (front, back) = self.as_slices();
+        let count = out.len().min(self.len());
+        let (first, second) = out[..count].split_at_mut(count.min(front.len()));
+        first.copy_from_slice(&front[..first.len()]);
+        second.copy_from_slice(&back[..second.len()]);
+        count
+    }
 
-        queue.enqueue(24541);
-        queue.enqueue(55154);
-        queue.enqueue(38290);
-        assert_eq!(queue.dequeue(), Some(24541));
-        assert_eq!(queue.dequeue(), Some(55154));
-        assert_eq!(queue.dequeue(), Some(38290));
-        assert_eq!(queue.dequeue(), None);
+    #[inline(always)]
+    pub const fn peek(&self) -> Option<&T>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        Some(unsafe { self.slots()[self.head()].assume_init_ref() })
+    }
 
-        queue.enqueue(5996);
-        assert_eq!(queue.dequeue(), Some(5996));
-        assert_eq!(queue.dequeue(), None);
+    #[inline(always)]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            return None;
+        }
+        let head = self.head();
+        Some(unsafe { self.slots_mut()[head].assume_init_mut() })
+    }
 
-        queue.enqueue(26769);
-        queue.enqueue(64004);
-        queue.enqueue(63460);
-        assert_eq!(queue.dequeue(), Some(26769));
-        assert_eq!(queue.dequeue(), Some(64004));
-        assert_eq!(queue.dequeue(), Some(63460));
-        assert_eq!(queue.dequeue(), None);
+    /// Returns the `n`-th oldest element, where `get(0)` is the front.
+    #[inline(always)]
+    pub const fn get(&self, n: usize) -> Option<&T>
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        if n >= self.len() {
+            return None;
+        }
+        Some(unsafe { self.slots()[self.slot(n)].assume_init_ref() })
     }
 
-    #[test]
-    fn test_fails_when_queueing_n() {
-        let mut queue: Queue<u32, 4> = Queue::new();
+    #[inline(always)]
+    pub fn get_mut(&mut self, n: usize) -> Option<&mut T> {
+        if n >= self.len() {
+            return None;
+        }
+        let slot = self.slot(n);
+        Some(unsafe { self.slots_mut()[slot].assume_init_mut() })
+    }
 
-        queue.enqueue(26769);
-        queue.enqueue(64004);
-        queue.enqueue(63460);
-        queue.enqueue(857);
-        assert!(queue.is_empty());
+    #[inline(always)]
+    pub fn back(&self) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        let last = self.slot(self.len() - 1);
+        Some(unsafe { self.slots()[last].assume_init_ref() })
+    }
+
+    #[inline(always)]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            return None;
+        }
+        let last = self.slot(self.len() - 1);
+        Some(unsafe { self.slots_mut()[last].assume_init_mut() })
+    }
+
+    /// Removes and returns the `n`-th oldest element, shifting whichever side of it is shorter
+    /// to close the gap, so the order of the rest is preserved.
+    #[inline(always)]
+    pub fn remove(&mut self, n: usize) -> Option<T> {
+        let len = self.len();
+        if n >= len {
+            return None;
+        }
+        let item = unsafe { self.slots()[self.slot(n)].assume_init_read() };
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_variables))]
+        let vacated = if n < len / 2 {
+            for i in (0..n).rev() {
+                self.shift(i, i + 1);
+            }
+            let head = self.head();
+            self.head = I::from_usize(self.slot(1));
+            head
+        } else {
+            for i in n + 1..len {
+                self.shift(i, i - 1);
+            }
+            self.slot(len - 1)
+        };
+        self.len = I::from_usize(len - 1);
+        #[cfg(feature = "zeroize")]
+        self.slots_mut()[vacated].zeroize();
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(1);
+        Some(item)
+    }
+
+    /// Removes and returns the `n`-th oldest element in O(1) by moving the newest element into
+    /// its place, so the order of the rest is not preserved.
+    #[inline(always)]
+    pub fn swap_remove_back(&mut self, n: usize) -> Option<T> {
+        let len = self.len();
+        if n >= len {
+            return None;
+        }
+        let item = unsafe { self.slots()[self.slot(n)].assume_init_read() };
+        if n != len - 1 {
+            self.shift(len - 1, n);
+        }
+        self.len = I::from_usize(len - 1);
+        #[cfg(feature = "zeroize")]
+        {
+            let last = self.slot(len - 1);
+            self.slots_mut()[last].zeroize();
+        }
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(1);
+        Some(item)
+    }
+
+    /// Moves the `from`-th oldest element into the `to`-th position, leaving its old slot
+    /// logically uninitialized.
+    #[inline(always)]
+    fn shift(&mut self, from: usize, to: usize) {
+        let item = unsafe { self.slots()[self.slot(from)].assume_init_read() };
+        let to = self.slot(to);
+        self.slots_mut()[to].write(item);
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, preserving their order.
+    #[inline(always)]
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let len = self.len();
+        // Should `f` panic, the remaining elements are leaked rather than dropped twice.
+        self.len = I::ZERO;
+        let mut kept = 0;
+        for i in 0..len {
+            let slot = self.slot(i);
+            if f(unsafe { self.slots()[slot].assume_init_ref() }) {
+                let item = unsafe { self.slots()[slot].assume_init_read() };
+                let to = self.slot(kept);
+                self.slots_mut()[to].write(item);
+                kept += 1;
+            } else {
+                unsafe { self.slots_mut()[slot].assume_init_drop() };
+            }
+        }
+        #[cfg(feature = "zeroize")]
+        for i in kept..len {
+            let slot = self.slot(i);
+            self.slots_mut()[slot].zeroize();
+        }
+        self.len = I::from_usize(kept);
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(len - kept);
+    }
+
+    /// Discards the newest elements so that at most `len` remain. Has no effect if the queue
+    /// already holds `len` elements or fewer.
+    #[inline(always)]
+    pub fn truncate(&mut self, len: usize) {
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(self.len().saturating_sub(len));
+        if !mem::needs_drop::<T>() && !cfg!(feature = "zeroize") {
+            if len < self.len() {
+                self.len = I::from_usize(len);
+            }
+            return;
+        }
+        while self.len() > len {
+            let last = self.slot(self.len() - 1);
+            self.len = I::from_usize(self.len() - 1);
+            let slot = &mut self.slots_mut()[last];
+            unsafe { slot.assume_init_drop() };
+            #[cfg(feature = "zeroize")]
+            slot.zeroize();
+        }
+    }
+
+    /// Enqueues items from `iter` until the queue is full, returning how many were taken. No
+    /// item is pulled that does not fit, so passing `iter.by_ref()` lets the next call resume
+    /// where this one stopped.
+    #[inline(always)]
+    pub fn extend_until_full(&mut self, iter: impl IntoIterator<Item = T>) -> usize {
+        let mut iter = iter.into_iter();
+        let mut count = 0;
+        while !self.is_full() {
+            match iter.next() {
+                Some(item) => self.enqueue(item),
+                None => break,
+            }
+            count += 1;
+        }
+        count
+    }
+
+    /// Moves every element after the oldest `at` into a new queue, keeping their order. Returns
+    /// an empty queue if the queue holds `at` elements or fewer.
+    #[inline(always)]
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        S: OwnedStorage<T>,
+    {
+        let mut other = Self::from_storage(self.buffer.new_like());
+        let len = self.len();
+        if at >= len {
+            return other;
+        }
+        self.len = I::from_usize(at);
+        #[cfg(feature = "stats")]
+        self.stats.dequeued(len - at);
+        for i in at..len {
+            let slot = self.slot(i);
+            let slot = &mut self.slots_mut()[slot];
+            let item = unsafe { slot.assume_init_read() };
+            #[cfg(feature = "zeroize")]
+            slot.zeroize();
+            let _ = other.try_enqueue(item);
+        }
+        other
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        self.head = I::ZERO;
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
+    }
+
+    #[inline(always)]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self)
+    }
+
+    #[inline(always)]
+    pub fn drain(&mut self) -> Drain<'_, T, S, I, P> {
+        Drain::new(self)
+    }
+
+    /// Dequeues the elements in batches of `size`, the last one possibly shorter, e.g. to fill
+    /// one packet per iteration. Yields nothing if `size` is 0.
+    #[inline(always)]
+    pub fn chunks(&mut self, size: usize) -> Chunks<'_, T, S, I, P>
+    where
+        S: OwnedStorage<T>,
+    {
+        Chunks::new(self, size)
+    }
+
+    /// Returns the live elements as two slices, oldest first. The second slice is non-empty
+    /// only when the elements wrap around the end of the buffer.
+    #[inline(always)]
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (head, len) = (self.head(), self.len());
+        let first = len.min(self.capacity() - head);
+        let slots = self.slots();
+        unsafe {
+            (
+                slots[head..head + first].assume_init_ref(),
+                slots[..len - first].assume_init_ref(),
+            )
+        }
+    }
+
+    #[inline(always)]
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (head, len) = (self.head(), self.len());
+        let first = len.min(self.capacity() - head);
+        let (wrapped, front) = self.slots_mut().split_at_mut(head);
+        unsafe {
+            (
+                front[..first].assume_init_mut(),
+                wrapped[..len - first].assume_init_mut(),
+            )
+        }
+    }
+
+    /// Rotates the buffer in place, if needed, so that the live elements form a single slice.
+    #[inline(always)]
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let (mut head, len) = (self.head(), self.len());
+        if head + len > self.capacity() {
+            self.slots_mut().rotate_left(head);
+            self.head = I::ZERO;
+            head = 0;
+        }
+        unsafe { self.slots_mut()[head..head + len].assume_init_mut() }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, item: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        let (first, second) = self.as_slices();
+        first.contains(item) || second.contains(item)
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool
+    where
+        I: [const] QueueIndex,
+    {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub const fn is_full(&self) -> bool
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        self.len() == self.capacity()
+    }
+
+    #[inline(always)]
+    pub const fn len(&self) -> usize
+    where
+        I: [const] QueueIndex,
+    {
+        self.len.to_usize()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize
+    where
+        S: [const] Storage<T>,
+    {
+        self.slots().len()
+    }
+
+    #[inline(always)]
+    pub const fn remaining_capacity(&self) -> usize
+    where
+        S: [const] Storage<T>,
+        I: [const] QueueIndex,
+    {
+        self.capacity() - self.len()
+    }
+
+    #[cfg(feature = "stats")]
+    #[inline(always)]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// How often an enqueue left the queue at each depth, see [`Stats::occupancy`]. Mostly
+    /// empty buckets at the top mean the capacity can shrink; hits in the last one mean it is
+    /// close to overflowing.
+    #[cfg(feature = "stats")]
+    #[inline(always)]
+    pub fn occupancy_histogram(&self) -> [usize; OCCUPANCY_BUCKETS] {
+        self.stats.occupancy
+    }
+
+    /// Zeroes the counters and restarts the high-water mark at the current length.
+    #[cfg(feature = "stats")]
+    #[inline(always)]
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats {
+            high_water: self.len(),
+            ..Stats::new()
+        };
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex, P: Policy> Default for Queue<T, N, I, P> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Drop for StorageQueue<T, S, I, P> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        self.zeroize();
+        #[cfg(not(feature = "zeroize"))]
+        self.truncate(0);
+    }
+}
+
+/// Drops the elements and wipes the whole buffer, including slots that no longer hold one.
+#[cfg(feature = "zeroize")]
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Zeroize for StorageQueue<T, S, I, P> {
+    fn zeroize(&mut self) {
+        self.clear();
+        self.slots_mut().zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> ZeroizeOnDrop for StorageQueue<T, S, I, P> {}
+
+/// Clones only the live elements; the clone starts at the front of its buffer.
+impl<T: Clone, S: OwnedStorage<T>, I: QueueIndex, P: Policy> Clone for StorageQueue<T, S, I, P> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        let mut queue = Self::from_storage(self.buffer.new_like());
+        queue.extend(self.iter().cloned());
+        queue
+    }
+}
+
+/// Queues are equal when they hold the same elements in the same order, regardless of where
+/// those elements sit in the ring or what storage they use.
+impl<T, S, R, I, J, P, Q> PartialEq<StorageQueue<T, R, J, Q>> for StorageQueue<T, S, I, P>
+where
+    T: PartialEq,
+    S: Storage<T>,
+    R: Storage<T>,
+    I: QueueIndex,
+    J: QueueIndex,
+    P: Policy,
+    Q: Policy,
+{
+    #[inline(always)]
+    fn eq(&self, other: &StorageQueue<T, R, J, Q>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, S: Storage<T>, I: QueueIndex, P: Policy> Eq for StorageQueue<T, S, I, P> {}
+
+/// Indexes by FIFO position, where `queue[0]` is the front. Panics if out of range.
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Index<usize> for StorageQueue<T, S, I, P> {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, n: usize) -> &T {
+        self.get(n).expect("index out of range")
+    }
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> IndexMut<usize> for StorageQueue<T, S, I, P> {
+    #[inline(always)]
+    fn index_mut(&mut self, n: usize) -> &mut T {
+        self.get_mut(n).expect("index out of range")
+    }
+}
+
+impl<T: Hash, S: Storage<T>, I: QueueIndex, P: Policy> Hash for StorageQueue<T, S, I, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: fmt::Debug, S: Storage<T>, I: QueueIndex, P: Policy> fmt::Debug
+    for StorageQueue<T, S, I, P>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> StorageQueue<u8, S, I, P> {
+    /// The queued bytes as text, without copying them out. The two halves of
+    /// [`Self::as_slices`] are checked separately, so a character split by the wrap point is an
+    /// error; [`Self::make_contiguous`] first to rule that out.
+    #[inline(always)]
+    pub fn as_str(&self) -> Result<(&str, &str), Utf8Error> {
+        let (front, back) = self.as_slices();
+        Ok((str::from_utf8(front)?, str::from_utf8(back)?))
+    }
+}
+
+/// Writes each string whole or not at all: when it does not fit, the queue is left unchanged and
+/// `fmt::Error` is returned, so a full queue never ends up holding half a UTF-8 character.
+impl<S: Storage<u8>, I: QueueIndex, P: Policy> fmt::Write for StorageQueue<u8, S, I, P> {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.enqueue_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
+/// Enqueues items until the queue is full; the rest of the iterator is left unconsumed.
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Extend<T> for StorageQueue<T, S, I, P> {
+    #[inline(always)]
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.extend_until_full(iter);
+    }
+}
+
+impl<'a, T: Copy + 'a, S: Storage<T>, I: QueueIndex, P: Policy> Extend<&'a T>
+    for StorageQueue<T, S, I, P>
+{
+    #[inline(always)]
+    fn extend<It: IntoIterator<Item = &'a T>>(&mut self, iter: It) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// Collects at most [`Queue::CAPACITY`] items; any further items are ignored.
+impl<T, const N: usize, I: QueueIndex, P: Policy> FromIterator<T> for Queue<T, N, I, P> {
+    #[inline(always)]
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+    use std::hash::{BuildHasher, RandomState};
+    use std::rc::Rc;
+    use std::string::String;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_it_works() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(4711);
+        queue.enqueue(7690);
+        assert_eq!(queue.dequeue(), Some(4711));
+        assert_eq!(queue.dequeue(), Some(7690));
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(34129);
+        queue.enqueue(33833);
+        assert_eq!(queue.dequeue(), Some(34129));
+        assert_eq!(queue.dequeue(), Some(33833));
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(41272);
+        queue.enqueue(26343);
+        assert_eq!(queue.dequeue(), Some(41272));
+        assert_eq!(queue.dequeue(), Some(26343));
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(45354);
+        assert_eq!(queue.dequeue(), Some(45354));
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(24541);
+        queue.enqueue(55154);
+        queue.enqueue(38290);
+        assert_eq!(queue.dequeue(), Some(24541));
+        assert_eq!(queue.dequeue(), Some(55154));
+        assert_eq!(queue.dequeue(), Some(38290));
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(5996);
+        assert_eq!(queue.dequeue(), Some(5996));
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.enqueue(63460);
+        assert_eq!(queue.dequeue(), Some(26769));
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(queue.dequeue(), Some(63460));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_holds_n_elements() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.enqueue(63460);
+        queue.enqueue(857);
+        assert!(queue.is_full());
+        #[cfg(not(feature = "checked"))]
+        queue.enqueue(4711);
+        assert_eq!(queue.dequeue(), Some(26769));
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(queue.dequeue(), Some(63460));
+        assert_eq!(queue.dequeue(), Some(857));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_len_and_capacity() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.capacity(), 4);
+        assert_eq!(queue.len(), 0);
+
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.enqueue(3);
+        assert_eq!(queue.len(), 3);
+
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue(4);
+        queue.enqueue(5);
+        queue.enqueue(6);
+        assert_eq!(queue.len(), 4);
+        queue.dequeue();
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn test_is_full() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert!(!queue.is_full());
+
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        assert!(!queue.is_full());
+        queue.enqueue(63460);
+        queue.enqueue(857);
+        assert!(queue.is_full());
+
+        queue.dequeue();
+        assert!(!queue.is_full());
+        queue.enqueue(4711);
+        assert!(queue.is_full());
+    }
+
+    #[test]
+    fn test_try_enqueue_rejects_when_full() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.try_enqueue(26769), Ok(()));
+        assert_eq!(queue.try_enqueue(64004), Ok(()));
+        assert_eq!(queue.try_enqueue(63460), Ok(()));
+        assert_eq!(queue.try_enqueue(857), Ok(()));
+        assert_eq!(queue.try_enqueue(4711), Err(QueueFull(4711)));
+        assert_eq!(queue.len(), 4);
+
+        assert_eq!(queue.dequeue(), Some(26769));
+        assert_eq!(queue.try_enqueue(4711), Ok(()));
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(queue.dequeue(), Some(63460));
+        assert_eq!(queue.dequeue(), Some(857));
+        assert_eq!(queue.dequeue(), Some(4711));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_enqueue_overwrite_evicts_oldest() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.enqueue_overwrite(26769), None);
+        assert_eq!(queue.enqueue_overwrite(64004), None);
+        assert_eq!(queue.enqueue_overwrite(63460), None);
+        assert_eq!(queue.enqueue_overwrite(5996), None);
+        assert_eq!(queue.enqueue_overwrite(857), Some(26769));
+        assert_eq!(queue.enqueue_overwrite(4711), Some(64004));
+        assert_eq!(queue.len(), 4);
+
+        assert_eq!(queue.dequeue(), Some(63460));
+        assert_eq!(queue.dequeue(), Some(5996));
+        assert_eq!(queue.dequeue(), Some(857));
+        assert_eq!(queue.dequeue(), Some(4711));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_peek_does_not_consume() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.peek(), None);
+
+        queue.enqueue(4711);
+        queue.enqueue(7690);
+        assert_eq!(queue.peek(), Some(&4711));
+        assert_eq!(queue.peek(), Some(&4711));
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.dequeue(), Some(4711));
+        assert_eq!(queue.peek().copied(), Some(7690));
+        assert_eq!(queue.dequeue(), Some(7690));
+        assert_eq!(queue.peek(), None);
+    }
+
+    #[test]
+    fn test_peek_mut_modifies_front() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.peek_mut(), None);
+
+        queue.enqueue(4711);
+        queue.enqueue(7690);
+        *queue.peek_mut().unwrap() += 1;
+        assert_eq!(queue.dequeue(), Some(4712));
+        assert_eq!(queue.dequeue(), Some(7690));
+    }
+
+    #[test]
+    fn test_back_across_wrap() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.back(), None);
+        assert_eq!(queue.back_mut(), None);
+
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.enqueue(63460);
+        assert_eq!(queue.back(), Some(&63460));
+
+        queue.dequeue();
+        queue.enqueue(857);
+        assert_eq!(queue.back(), Some(&857));
+        *queue.back_mut().unwrap() = 858;
+        assert_eq!(queue.peek(), Some(&64004));
+
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(queue.dequeue(), Some(63460));
+        assert_eq!(queue.dequeue(), Some(858));
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.len(), 0);
+        assert_eq!(queue.dequeue(), None);
+
+        queue.enqueue(857);
+        assert_eq!(queue.dequeue(), Some(857));
+    }
+
+    #[test]
+    fn test_iter_across_wrap() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.iter().next(), None);
+
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+        queue.enqueue(4711);
+
+        let mut iter = queue.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(&63460));
+        assert_eq!(iter.next_back(), Some(&4711));
+        assert_eq!(iter.next(), Some(&857));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut sum = 0;
+        for item in &queue {
+            sum += item;
+        }
+        assert_eq!(sum, 63460 + 857 + 4711);
+        assert_eq!(queue.len(), 3);
+    }
+
+    #[test]
+    fn test_iter_mut_across_wrap() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+        queue.enqueue(4711);
+
+        for item in queue.iter_mut() {
+            *item += 1;
+        }
+        if let Some(last) = (&mut queue).into_iter().next_back() {
+            *last = 0;
+        }
+        assert_eq!(queue.dequeue(), Some(63461));
+        assert_eq!(queue.dequeue(), Some(858));
+        assert_eq!(queue.dequeue(), Some(0));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_into_iter_by_value() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.dequeue();
+        queue.enqueue(64004);
+        queue.enqueue(63460);
+        queue.enqueue(857);
+
+        let mut iter = queue.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(64004));
+        assert_eq!(iter.next_back(), Some(857));
+        assert_eq!(iter.next(), Some(63460));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(1);
+        queue.enqueue(2);
+        let mut expected = 1;
+        for item in queue {
+            assert_eq!(item, expected);
+            expected += 1;
+        }
+        assert_eq!(expected, 3);
+    }
+
+    #[test]
+    fn test_extend_stops_when_full() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.extend([26769, 64004]);
+        queue.extend(&[63460, 857, 4711]);
+        assert!(queue.is_full());
+        assert_eq!(queue.dequeue(), Some(26769));
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(queue.dequeue(), Some(63460));
+        assert_eq!(queue.dequeue(), Some(857));
+        assert_eq!(queue.dequeue(), None);
+
+        let mut source = 0..10;
+        queue.extend(&mut source);
+        assert_eq!(queue.len(), 4);
+        assert_eq!(source.next(), Some(4));
+    }
+
+    #[test]
+    fn test_collect_truncates_to_capacity() {
+        let mut queue: Queue<u32, 4> = (0..2).collect();
+        assert_eq!(queue.dequeue(), Some(0));
+        assert_eq!(queue.dequeue(), Some(1));
+        assert_eq!(queue.dequeue(), None);
+
+        let mut queue: Queue<u32, 4> = (0..10).collect();
+        assert_eq!(queue.len(), 4);
+        for i in 0..4 {
+            assert_eq!(queue.dequeue(), Some(i));
+        }
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_from_array() {
+        const FREE_LIST: Queue<u8, 8> = Queue::from_array([0, 1, 2, 3, 4, 5, 6, 7]);
+        let mut queue = FREE_LIST;
+        assert!(queue.is_full());
+        for i in 0..8 {
+            assert_eq!(queue.dequeue(), Some(i));
+        }
+        assert_eq!(queue.dequeue(), None);
+
+        let queue: Queue<u32, 4> = Queue::from_array([]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_const_enqueue_dequeue() {
+        static BOOT_SCRIPT: Queue<u16, 4, u8> = {
+            let mut queue = Queue::new();
+            let mut command = 26769;
+            while queue.try_enqueue(command).is_ok() {
+                command += 1;
+            }
+            let _ = queue.dequeue();
+            let _ = queue.try_enqueue(4711);
+            queue
+        };
+        const FRONT: Option<u16> = match BOOT_SCRIPT.peek() {
+            Some(command) => Some(*command),
+            None => None,
+        };
+        assert_eq!(FRONT, Some(26770));
+        assert!(BOOT_SCRIPT.iter().copied().eq([26770, 26771, 26772, 4711]));
+
+        const RING: Queue<u8, 3, u8, Overwrite> = {
+            let mut queue = Queue::new();
+            let mut i = 0;
+            while i < 5 {
+                queue.enqueue(i);
+                i += 1;
+            }
+            queue
+        };
+        assert!(RING.iter().copied().eq([2, 3, 4]));
+    }
+
+    #[test]
+    fn test_debug_prints_live_elements() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(format!("{:?}", queue), "[]");
+
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+        assert_eq!(format!("{:?}", queue), "[64004, 63460, 857]");
+    }
+
+    #[test]
+    fn test_clone_is_independent() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+
+        let mut snapshot = queue.clone();
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(snapshot.len(), 3);
+        assert_eq!(snapshot.dequeue(), Some(64004));
+        assert_eq!(snapshot.dequeue(), Some(63460));
+        assert_eq!(snapshot.dequeue(), Some(857));
+        assert_eq!(snapshot.dequeue(), None);
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn test_eq_ignores_head_offset() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+
+        let expected: Queue<u32, 4> = Queue::from_array([63460, 857]);
+        assert_eq!(queue, expected);
+        let wider: Queue<u32, 8> = Queue::from_array([63460, 857]);
+        assert_eq!(queue, wider);
+
+        queue.dequeue();
+        assert_ne!(queue, expected);
+        assert_eq!(queue, Queue::<u32, 4>::from_array([857]));
+    }
+
+    #[test]
+    fn test_hash_matches_eq() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+        queue.enqueue(4711);
+
+        let state = RandomState::new();
+        let expected: Queue<u32, 4> = Queue::from_array([63460, 857, 4711]);
+        assert_eq!(state.hash_one(&queue), state.hash_one(&expected));
+
+        queue.dequeue();
+        assert_ne!(state.hash_one(&queue), state.hash_one(&expected));
+    }
+
+    #[test]
+    fn test_enqueue_slice_across_wrap() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        assert_eq!(queue.enqueue_slice(&[]), 0);
+        assert_eq!(queue.enqueue_slice(&[1, 2, 3, 4, 5]), 5);
+        for i in 1..=5 {
+            assert_eq!(queue.dequeue(), Some(i));
+        }
+
+        assert_eq!(queue.enqueue_slice(&[6, 7, 8, 9, 10, 11, 12, 13, 14]), 8);
+        assert!(queue.is_full());
+        assert_eq!(queue.enqueue_slice(&[15]), 0);
+        for i in 6..=13 {
+            assert_eq!(queue.dequeue(), Some(i));
+        }
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_dequeue_into_across_wrap() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        let mut out = [0; 10];
+        assert_eq!(queue.dequeue_into(&mut out), 0);
+
+        queue.enqueue_slice(&[1, 2, 3, 4, 5]);
+        assert_eq!(queue.dequeue_into(&mut out[..3]), 3);
+        assert_eq!(out[..3], [1, 2, 3]);
+
+        queue.enqueue_slice(&[6, 7, 8, 9, 10]);
+        assert_eq!(queue.dequeue_into(&mut out), 7);
+        assert_eq!(out[..7], [4, 5, 6, 7, 8, 9, 10]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_transfer_across_wrap() {
+        let mut staging: Queue<u32, 4> = Queue::new();
+        let mut central: Queue<u32, 6, u8> = Queue::new();
+        let mut expected = 0..;
+        let mut next = 0;
+
+        for _ in 0..10 {
+            while staging.try_enqueue(next).is_ok() {
+                next += 1;
+            }
+            staging.transfer(&mut central, 3);
+            assert_eq!(staging.len(), 1);
+            while central.len() > 2 {
+                assert_eq!(central.dequeue(), expected.next());
+            }
+        }
+        assert_eq!(staging.transfer(&mut central, 0), 0);
+        central.enqueue_slice(&[0; 3]);
+        assert_eq!(staging.transfer(&mut central, 5), 1);
+        assert!(central.is_full());
+    }
+
+    #[test]
+    fn test_transfer_moves_ownership() {
+        let token = Rc::new(());
+        let mut src: Queue<Rc<()>, 3> = Queue::new();
+        let mut dst: Queue<Rc<()>, 2> = Queue::new();
+        src.extend([token.clone(), token.clone(), token.clone()]);
+        assert_eq!(src.transfer(&mut dst, 3), 2);
+        assert_eq!(Rc::strong_count(&token), 4);
+        assert_eq!(src.append(&mut dst), 2);
+        assert_eq!((src.len(), dst.len()), (3, 0));
+        assert_eq!(dst.append(&mut src), 2);
+        drop(src);
+        assert_eq!(Rc::strong_count(&token), 3);
+        drop(dst);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    fn test_copy_to_slice_leaves_queue_intact() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        let mut out = [0; 10];
+        assert_eq!(queue.copy_to_slice(&mut out), 0);
+
+        queue.enqueue_slice(&[1, 2, 3, 4, 5]);
+        queue.advance(3);
+        queue.enqueue_slice(&[6, 7, 8, 9, 10]);
+        assert_eq!(queue.copy_to_slice(&mut out[..4]), 4);
+        assert_eq!(out[..4], [4, 5, 6, 7]);
+        assert_eq!(queue.copy_to_slice(&mut out), 7);
+        assert_eq!(out[..7], [4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(queue.len(), 7);
+        assert_eq!(queue.peek(), Some(&4));
+    }
+
+    #[test]
+    fn test_drain_empties_queue() {
+        let mut queue: Queue<u32, 4> = Queue::from_array([26769, 64004, 63460]);
+        let mut drain = queue.drain();
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next(), Some(26769));
+        drop(drain);
+        assert!(queue.is_empty());
+
+        queue.extend([857, 4711]);
+        let mut sum = 0;
+        for item in queue.drain() {
+            sum += item;
+        }
+        assert_eq!(sum, 857 + 4711);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_as_slices() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        assert_eq!(queue.as_slices(), (&[][..], &[][..]));
+
+        queue.enqueue_slice(&[1, 2, 3, 4, 5]);
+        assert_eq!(queue.as_slices(), (&[1, 2, 3, 4, 5][..], &[][..]));
+
+        queue.dequeue_into(&mut [0; 4]);
+        queue.enqueue_slice(&[6, 7, 8, 9, 10]);
+        assert_eq!(queue.as_slices(), (&[5, 6, 7, 8][..], &[9, 10][..]));
+
+        let (first, second) = queue.as_mut_slices();
+        first[0] = 50;
+        second[1] = 100;
+        assert_eq!(queue.dequeue(), Some(50));
+        assert_eq!(queue.back(), Some(&100));
+    }
+
+    #[test]
+    fn test_make_contiguous() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        assert_eq!(queue.make_contiguous(), &mut [0; 0]);
+
+        queue.enqueue_slice(&[1, 2, 3, 4, 5]);
+        queue.dequeue_into(&mut [0; 2]);
+        assert_eq!(queue.make_contiguous(), &mut [3, 4, 5]);
+
+        queue.enqueue_slice(&[6, 7, 8, 9, 10]);
+        assert_eq!(queue.make_contiguous(), &mut [3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(queue.as_slices().1, &[0; 0]);
+        assert!(queue.is_full());
+        assert_eq!(queue.dequeue(), Some(3));
+        queue.enqueue(11);
+        assert_eq!(queue.make_contiguous(), &mut [4, 5, 6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn test_get_relative_to_head() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.get(0), None);
+
+        queue.enqueue_slice(&[26769, 64004, 63460]);
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue_slice(&[857, 4711]);
+        assert_eq!(queue.get(0), Some(&63460));
+        assert_eq!(queue.get(1), Some(&857));
+        assert_eq!(queue.get(2), Some(&4711));
+        assert_eq!(queue.get(3), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert!(!queue.contains(&26769));
+
+        queue.enqueue_slice(&[26769, 64004, 63460]);
+        queue.dequeue();
+        queue.enqueue(857);
+        assert!(!queue.contains(&26769));
+        assert!(queue.contains(&64004));
+        assert!(queue.contains(&857));
+    }
+
+    #[test]
+    fn test_retain_across_wrap() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        queue.enqueue_slice(&[0; 5]);
+        queue.dequeue_into(&mut [0; 5]);
+        queue.enqueue_slice(&[1, 2, 3, 4, 5, 6, 7]);
+
+        queue.retain(|item| item % 2 == 1);
+        assert_eq!(queue, Queue::<u32, 8>::from_array([1, 3, 5, 7]));
+        queue.enqueue(9);
+        assert_eq!(queue.back(), Some(&9));
+
+        queue.retain(|_| false);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_remove_across_wrap() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        queue.enqueue_slice(&[0; 5]);
+        queue.dequeue_into(&mut [0; 5]);
+        queue.enqueue_slice(&[1, 2, 3, 4, 5, 6, 7]);
+
+        assert_eq!(queue.remove(7), None);
+        assert_eq!(queue.remove(1), Some(2));
+        assert_eq!(queue.remove(4), Some(6));
+        assert_eq!(queue, Queue::<u32, 8>::from_array([1, 3, 4, 5, 7]));
+        assert_eq!(queue.remove(0), Some(1));
+        assert_eq!(queue.remove(3), Some(7));
+        assert_eq!(queue, Queue::<u32, 8>::from_array([3, 4, 5]));
+    }
+
+    #[test]
+    fn test_swap_remove_back() {
+        let token = Rc::new(());
+        let mut queue: Queue<(u32, Rc<()>), 4> = Queue::new();
+        for id in [26769, 64004, 63460] {
+            queue.enqueue((id, token.clone()));
+        }
+        assert!(queue.swap_remove_back(3).is_none());
+        assert_eq!(queue.swap_remove_back(0).map(|(id, _)| id), Some(26769));
+        assert_eq!(queue.swap_remove_back(1).map(|(id, _)| id), Some(64004));
+        assert!(queue.iter().map(|(id, _)| *id).eq([63460]));
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    fn test_advance_discards_oldest() {
+        let mut queue: Queue<u8, 8> = Queue::new();
+        for round in 0..5 {
+            queue.enqueue_slice(&[round, round, 0x7E, round + 1, round + 2]);
+            let marker = queue.iter().position(|&byte| byte == 0x7E).unwrap();
+            assert_eq!(queue.advance(marker + 1), 3);
+            assert_eq!(queue.dequeue(), Some(round + 1));
+            assert_eq!(queue.advance(5), 1);
+            assert_eq!(queue.advance(1), 0);
+        }
+
+        let token = Rc::new(());
+        let mut queue: Queue<Rc<()>, 4> = Queue::new();
+        queue.extend([token.clone(), token.clone(), token.clone()]);
+        assert_eq!(queue.advance(2), 2);
+        assert_eq!(Rc::strong_count(&token), 2);
+    }
+
+    #[test]
+    fn test_split_off_across_wrap() {
+        let mut queue: Queue<u32, 4> = Queue::from_array([26769, 64004, 63460]);
+        queue.advance(2);
+        queue.enqueue_slice(&[857, 4711, 1]);
+
+        assert!(queue.split_off(4).is_empty());
+        let newer = queue.split_off(1);
+        assert_eq!(queue, Queue::<u32, 4>::from_array([63460]));
+        assert_eq!(newer, Queue::<u32, 4>::from_array([857, 4711, 1]));
+        queue.enqueue_slice(&[2, 3, 4]);
+        assert!(queue.iter().copied().eq([63460, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_index_by_fifo_position() {
+        let mut queue: Queue<u32, 4> = Queue::from_array([26769, 64004, 63460]);
+        queue.advance(2);
+        queue.enqueue_slice(&[857, 4711]);
+        assert_eq!(queue[0], 63460);
+        assert_eq!(queue[2], 4711);
+        queue[1] += 1;
+        assert_eq!(queue.get_mut(1), Some(&mut 858));
+        assert_eq!(queue.get_mut(3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of range")]
+    fn test_index_out_of_range_panics() {
+        let queue: Queue<u32, 4> = Queue::from_array([26769]);
+        let _ = queue[1];
+    }
+
+    #[test]
+    fn test_peek_contiguous_and_release() {
+        let mut queue: Queue<u8, 8> = Queue::new();
+        assert!(queue.peek_contiguous().is_empty());
+        queue.enqueue_slice(b"xxxxx");
+        queue.advance(5);
+        queue.enqueue_slice(b"hello");
+
+        assert_eq!(queue.peek_contiguous(), b"hel");
+        assert_eq!(queue.release(2), 2);
+        assert_eq!(queue.peek_contiguous(), b"l");
+        queue.release(1);
+        assert_eq!(queue.peek_contiguous(), b"lo");
+        assert_eq!(queue.release(5), 2);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_extend_until_full_resumes() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        let mut samples = 0..10;
+        assert_eq!(queue.extend_until_full(samples.by_ref()), 4);
+        assert_eq!(queue.extend_until_full(samples.by_ref()), 0);
+        queue.advance(3);
+        assert_eq!(queue.extend_until_full(samples.by_ref()), 3);
+        assert!(queue.iter().copied().eq(3..7));
+        queue.clear();
+        assert_eq!(queue.extend_until_full(samples), 3);
+    }
+
+    #[test]
+    fn test_enqueue_all_or_nothing() {
+        let mut queue: Queue<u16, 5> = Queue::new();
+        assert_eq!(queue.enqueue_all(&[0x7E, 1, 2]), Ok(()));
+        assert_eq!(
+            queue.enqueue_all(&[0x7E, 3, 4]),
+            Err(QueueFull(&[0x7E, 3, 4][..]))
+        );
+        assert_eq!(queue.len(), 3);
+        queue.advance(2);
+        assert_eq!(queue.enqueue_all(&[0x7E, 3, 4, 5]), Ok(()));
+        assert!(queue.iter().copied().eq([2, 0x7E, 3, 4, 5]));
+        assert_eq!(queue.enqueue_all(&[]), Ok(()));
+    }
+
+    #[test]
+    fn test_dequeue_exact_never_tears() {
+        let mut queue: Queue<u8, 8> = Queue::new();
+        assert_eq!(queue.dequeue_exact::<0>(), Some([]));
+        for round in 0..6 {
+            queue.enqueue_slice(&[round, round + 1, round + 2]);
+            assert_eq!(queue.dequeue_exact::<4>(), None);
+            assert_eq!(queue.len(), 3);
+            queue.enqueue(round + 3);
+            assert_eq!(
+                queue.dequeue_exact(),
+                Some([round, round + 1, round + 2, round + 3])
+            );
+        }
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_chunks_by_value() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        queue.enqueue_slice(&[0; 6]);
+        queue.advance(6);
+        queue.enqueue_slice(&[1, 2, 3, 4, 5, 6, 7]);
+
+        let mut chunks = queue.chunks(3);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.next(), Some(Queue::<u32, 8>::from_array([1, 2, 3])));
+        assert_eq!(queue.len(), 4);
+        let sizes: Vec<usize> = queue.chunks(3).map(|chunk| chunk.len()).collect();
+        assert_eq!(sizes, [3, 1]);
+        assert!(queue.is_empty());
+        queue.enqueue(8);
+        assert_eq!(queue.chunks(0).next(), None);
+    }
+
+    #[test]
+    fn test_free_slices_receive() {
+        let mut queue: Queue<u8, 8> = Queue::new();
+        queue.enqueue_slice(b"xxxxxab");
+        queue.advance(5);
+
+        let (first, second) = queue.free_slices_mut();
+        assert_eq!((first.len(), second.len()), (1, 5));
+        first.write_copy_of_slice(b"c");
+        second[..2].write_copy_of_slice(b"de");
+        unsafe { queue.assume_written(3) };
+        assert!(queue.iter().eq(b"abcde"));
+
+        queue.enqueue_slice(b"fgh");
+        let (first, second) = queue.free_slices_mut();
+        assert_eq!((first.len(), second.len()), (0, 0));
+        queue.advance(4);
+        let (first, second) = queue.free_slices_mut();
+        assert_eq!((first.len(), second.len()), (3, 1));
+    }
+
+    #[test]
+    fn test_truncate_drops_newest() {
+        let mut queue: Queue<u32, 4> = Queue::from_array([26769, 64004]);
+        queue.dequeue();
+        queue.enqueue_slice(&[63460, 857]);
+
+        queue.truncate(5);
+        assert_eq!(queue.len(), 3);
+        queue.truncate(1);
+        assert_eq!(queue, Queue::<u32, 4>::from_array([64004]));
+        queue.truncate(0);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_remaining_capacity() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        assert_eq!(queue.remaining_capacity(), 4);
+        queue.enqueue_slice(&[26769, 64004]);
+        assert_eq!(queue.remaining_capacity(), 2);
+        queue.enqueue_slice(&[63460, 857]);
+        assert_eq!(queue.remaining_capacity(), 0);
+        queue.dequeue();
+        assert_eq!(queue.remaining_capacity(), 1);
+    }
+
+    #[test]
+    fn test_non_power_of_two_capacity() {
+        let mut queue: Queue<u32, 5> = Queue::new();
+        for round in 0..4 {
+            assert_eq!(queue.enqueue_slice(&[round, round + 1, round + 2]), 3);
+            assert_eq!(queue.dequeue(), Some(round));
+            assert_eq!(queue.dequeue(), Some(round + 1));
+            assert_eq!(queue.dequeue(), Some(round + 2));
+        }
+        queue.extend(0..10);
+        assert!(queue.is_full());
+        assert_eq!(queue.as_slices(), (&[0, 1, 2][..], &[3, 4][..]));
+        assert_eq!(
+            queue.iter().rev().copied().collect::<Queue<u32, 5>>(),
+            Queue::<u32, 5>::from_array([4, 3, 2, 1, 0])
+        );
+    }
+
+    #[test]
+    fn test_single_slot_queue() {
+        let mut queue: Queue<u32, 1> = Queue::new();
+        assert_eq!(queue.try_enqueue(26769), Ok(()));
+        assert!(queue.is_full());
+        assert_eq!(queue.try_enqueue(64004), Err(QueueFull(64004)));
+        assert_eq!(queue.dequeue(), Some(26769));
+        assert_eq!(queue.try_enqueue(64004), Ok(()));
+        assert_eq!(queue.dequeue(), Some(64004));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_narrow_index_type() {
+        // Counters add their own fields.
+        #[cfg(not(feature = "stats"))]
+        {
+            assert_eq!(core::mem::size_of::<Queue<u8, 16, u8>>(), 18);
+            assert_eq!(core::mem::size_of::<Queue<u8, 256, u16>>(), 260);
+        }
+
+        let mut queue: Queue<u8, 255, u8> = (0..=255).collect();
+        assert_eq!(queue.len(), 255);
+        assert!(queue.is_full());
+        assert_eq!(queue.dequeue(), Some(0));
+        queue.enqueue(255);
+        assert_eq!(queue.back(), Some(&255));
+        assert_eq!(queue, (1..=255).collect::<Queue<u8, 255>>());
+    }
+
+    #[test]
+    fn test_zero_sized_tokens() {
+        #[cfg(not(feature = "stats"))]
+        assert_eq!(core::mem::size_of::<Queue<(), 3, u8>>(), 2);
+
+        let mut queue: Queue<(), 3, u8> = Queue::new();
+        for _ in 0..2 {
+            queue.enqueue(());
+            queue.enqueue(());
+            assert_eq!(queue.dequeue(), Some(()));
+        }
+        queue.enqueue(());
+        assert!(queue.is_full());
+        assert_eq!(queue.try_enqueue(()), Err(QueueFull(())));
+        let (front, back) = queue.as_slices();
+        assert_eq!(front.len() + back.len(), 3);
+        assert_eq!(queue.iter().count(), 3);
+
+        let mut other: Queue<(), 8> = Queue::new();
+        assert_eq!(queue.transfer(&mut other, 2), 2);
+        assert_eq!(other.enqueue_slice(&[(); 10]), 6);
+        assert_eq!(other.dequeue_into(&mut [(); 5]), 5);
+        assert_eq!(other.drain().count(), 3);
+        assert_eq!(queue.clone().len(), 1);
+    }
+
+    #[test]
+    fn test_zero_sized_elements_drop() {
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+        struct Token;
+        impl Drop for Token {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut queue: Queue<Token, 4> = Queue::new();
+        queue.extend_until_full(core::iter::repeat_with(|| Token));
+        assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+        drop(queue.dequeue());
+        queue.truncate(2);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+        queue.enqueue(Token);
+        drop(queue);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 5);
+    }
+
+    #[test]
+    fn test_non_copy_elements() {
+        let token = Rc::new(());
+        let mut queue: Queue<Rc<()>, 4> = Queue::new();
+        for _ in 0..6 {
+            let _ = queue.try_enqueue(token.clone());
+        }
+        assert_eq!(Rc::strong_count(&token), 5);
+
+        let front = queue.dequeue().unwrap();
+        assert_eq!(Rc::strong_count(&token), 5);
+        drop(front);
+        assert_eq!(Rc::strong_count(&token), 4);
+
+        let snapshot = queue.clone();
+        assert_eq!(Rc::strong_count(&token), 7);
+        snapshot.into_iter().rev().for_each(drop);
+        queue.retain(|_| false);
+        assert_eq!(Rc::strong_count(&token), 1);
+
+        let mut queue: Queue<String, 4> = Queue::from_array([String::from("a"), String::from("b")]);
+        queue.enqueue(String::from("c"));
+        queue.truncate(2);
+        assert_eq!(queue.dequeue().as_deref(), Some("a"));
+        assert_eq!(queue.dequeue().as_deref(), Some("b"));
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let mut queue: Queue<Rc<()>, 4> = Queue::new();
+        for _ in 0..3 {
+            queue.enqueue(token.clone());
+        }
+        queue.dequeue();
+        queue.enqueue(token.clone());
+        queue.enqueue(token.clone());
+        assert_eq!(Rc::strong_count(&token), 5);
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+
+        let queue: Queue<Rc<()>, 4> = Queue::from_array([token.clone(), token.clone()]);
+        let mut iter = queue.into_iter();
+        iter.next();
+        assert_eq!(Rc::strong_count(&token), 2);
+        drop(iter);
+        assert_eq!(Rc::strong_count(&token), 1);
+
+        let mut queue: Queue<Rc<()>, 4> = Queue::from_array([token.clone(), token.clone()]);
+        queue.drain().next();
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    fn test_enqueue_with_builds_in_place() {
+        let mut queue: Queue<[u8; 256], 2> = Queue::new();
+        unsafe {
+            assert!(queue.enqueue_with(|slot| {
+                slot.write([0; 256])[..3].copy_from_slice(b"abc");
+            }));
+            assert!(queue.enqueue_with(|slot| {
+                let frame = slot.as_mut_ptr() as *mut u8;
+                frame.write_bytes(1, 256);
+            }));
+            assert!(!queue.enqueue_with(|_| unreachable!()));
+        }
+
+        assert_eq!(&queue.dequeue().unwrap()[..4], b"abc\0");
+        assert_eq!(queue.dequeue(), Some([1; 256]));
+    }
+
+    #[test]
+    fn test_stage_then_commit() {
+        let mut queue: Queue<[u8; 4], 2> = Queue::new();
+        let bytes = [0xde, 0xad, 0xbe, 0xef];
+        for (i, byte) in bytes.into_iter().enumerate() {
+            let slot = queue.stage().unwrap().as_mut_ptr() as *mut u8;
+            unsafe { slot.add(i).write(byte) };
+            assert!(queue.is_empty());
+        }
+        unsafe { queue.commit() };
+        assert_eq!(queue.len(), 1);
+
+        queue.stage().unwrap().write([7; 4]);
+        queue.abort();
+        assert_eq!(queue.len(), 1);
+        #[cfg(feature = "zeroize")]
+        assert_eq!(unsafe { queue.stage().unwrap().assume_init() }, [0; 4]);
+
+        queue.stage().unwrap().write([0; 4]);
+        queue.enqueue([1; 4]);
+        assert!(queue.is_full());
+        queue.abort();
+        assert!(queue.stage().is_none());
+        assert_eq!(queue.dequeue(), Some(bytes));
+        assert_eq!(queue.dequeue(), Some([1; 4]));
+    }
+
+    #[test]
+    fn test_fmt_write() {
+        use core::fmt::Write;
+
+        let mut queue: Queue<u8, 10> = Queue::new();
+        let (t1, t2) = (21, 100);
+        write!(queue, "temp={t1}").unwrap();
+        assert_eq!(queue.len(), 7);
+        assert_eq!(write!(queue, ";{t2}"), Err(fmt::Error));
+        // Pieces that fit before the overflow stay queued.
+        assert_eq!(queue.len(), 8);
+
+        let mut out = [0; 8];
+        queue.dequeue_into(&mut out);
+        assert_eq!(&out, b"temp=21;");
+    }
+
+    #[test]
+    fn test_as_str_across_wrap() {
+        let mut queue: Queue<u8, 8> = Queue::new();
+        queue.enqueue_slice(b"ok> ");
+        queue.advance(3);
+        queue.enqueue_slice("lsµ C".as_bytes());
+        assert_eq!(queue.as_str(), Ok((" lsµ", " C")));
+
+        queue.advance(5);
+        queue.enqueue_slice("°µ".as_bytes());
+        assert_eq!(queue.as_str(), Ok((" C°µ", "")));
+        queue.advance(4);
+        queue.enqueue_slice(b"o");
+        queue.enqueue_slice("µ".as_bytes());
+        assert!(queue.as_str().is_err());
+        queue.make_contiguous();
+        assert_eq!(queue.as_str(), Ok(("µoµ", "")));
+    }
+
+    #[test]
+    #[cfg(not(feature = "checked"))]
+    fn test_policy_decides_full_enqueue() {
+        let mut reject: Queue<u32, 2> = Queue::new();
+        let mut overwrite: Queue<u32, 2, usize, Overwrite> = Queue::new();
+        for item in 1..=3 {
+            reject.enqueue(item);
+            overwrite.enqueue(item);
+        }
+        assert!(reject.iter().copied().eq([1, 2]));
+        assert!(overwrite.iter().copied().eq([2, 3]));
+        assert_eq!(
+            reject,
+            overwrite
+                .clone()
+                .into_iter()
+                .map(|x| x - 1)
+                .collect::<Queue<_, 2>>()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "queue is full")]
+    fn test_panic_policy() {
+        let mut queue: Queue<u32, 1, u8, Panic> = Queue::new();
+        queue.enqueue(1);
+        queue.enqueue(2);
+    }
+
+    #[test]
+    #[cfg(feature = "checked")]
+    #[should_panic(expected = "enqueue on a full queue")]
+    fn test_checked_enqueue_panics_when_full() {
+        let mut overwrite: Queue<u32, 1, u8, Overwrite> = Queue::new();
+        overwrite.enqueue(1);
+        overwrite.enqueue(2);
+        let mut queue: Queue<u32, 1> = Queue::new();
+        queue.enqueue(1);
+        assert_eq!(queue.try_enqueue(2), Err(QueueFull(2)));
+        queue.enqueue(2);
+    }
+
+    #[test]
+    fn test_errors_propagate_with_question_mark() {
+        fn relay(from: &mut Queue<u32, 2>, to: &mut Queue<u32, 1>) -> Result<(), String> {
+            let item = from.try_dequeue().map_err(|e| format!("{e}"))?;
+            to.try_enqueue(item).map_err(|e| format!("{e}"))?;
+            Ok(())
+        }
+
+        let mut from: Queue<u32, 2> = Queue::from_array([1, 2]);
+        let mut to: Queue<u32, 1> = Queue::new();
+        assert_eq!(relay(&mut from, &mut to), Ok(()));
+        assert_eq!(relay(&mut from, &mut to), Err("queue is full".into()));
+        assert_eq!(relay(&mut from, &mut to), Err("queue is empty".into()));
+
+        let error: &dyn core::error::Error = &QueueFull(3);
+        assert_eq!(format!("{error}"), "queue is full");
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_wipes_vacated_slots() {
+        fn raw<const N: usize>(queue: &Queue<u8, N>) -> [u8; N] {
+            queue.buffer.map(|slot| unsafe { slot.assume_init() })
+        }
+
+        let mut queue: Queue<u8, 4> = Queue::from_array([1, 2, 3, 4]);
+        queue.dequeue();
+        let mut out = [0; 1];
+        queue.dequeue_into(&mut out);
+        assert_eq!(raw(&queue), [0, 0, 3, 4]);
+        queue.retain(|&x| x == 4);
+        assert_eq!(raw(&queue), [0, 0, 4, 0]);
+        queue.enqueue(5);
+        queue.zeroize();
+        assert_eq!(raw(&queue), [0; 4]);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_wipes_dequeued_slot() {
+        let mut queue: Queue<[u8; 4], 3> = Queue::from_array([*b"key0", *b"key1", *b"key2"]);
+        assert_eq!(queue.dequeue(), Some(*b"key0"));
+        assert_eq!(unsafe { queue.buffer[0].assume_init() }, [0; 4]);
+        assert_eq!(queue.take_back(), Some(*b"key2"));
+        assert_eq!(unsafe { queue.buffer[2].assume_init() }, [0; 4]);
+        assert_eq!(unsafe { queue.buffer[1].assume_init() }, *b"key1");
+    }
+
+    /// Checks that the hot path keeps no panic branch once optimized. Every call runs under a
+    /// guard whose drop glue names a symbol that does not exist, and the drop glue is only
+    /// kept if something in between can unwind, so a panic path fails the link. Debug builds
+    /// keep their overflow checks, so run with `cargo test --release`.
+    #[cfg(all(not(debug_assertions), not(feature = "checked")))]
+    mod no_panic {
+        use super::*;
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                unsafe extern "C" {
+                    #[link_name = "\n\nERROR: a queue operation on the hot path can panic\n\n"]
+                    fn hot_path_can_panic() -> !;
+                }
+                unsafe { hot_path_can_panic() }
+            }
+        }
+
+        macro_rules! no_panic {
+            ($($name:ident($($arg:ident: $ty:ty),*) -> $ret:ty = $call:expr;)*) => {
+                $(
+                    #[inline(never)]
+                    fn $name($($arg: $ty),*) -> $ret {
+                        let guard = Guard;
+                        let result = $call;
+                        mem::forget(guard);
+                        result
+                    }
+                )*
+            };
+        }
+
+        no_panic! {
+            try_enqueue(queue: &mut Queue<u32, 8>, item: u32) -> Result<(), QueueFull<u32>> = queue.try_enqueue(item);
+            enqueue(queue: &mut Queue<u32, 5, u8>, item: u32) -> () = queue.enqueue(item);
+            enqueue_overwrite(queue: &mut Queue<u32, 5, u8, Overwrite>, item: u32) -> () = queue.enqueue(item);
+            dequeue(queue: &mut Queue<u32, 5, u8>) -> Option<u32> = queue.dequeue();
+            dequeue_pow2(queue: &mut Queue<u32, 8>) -> Option<u32> = queue.dequeue();
+        }
+
+        #[test]
+        fn test_hot_path_links_without_panics() {
+            let mut pow2: Queue<u32, 8> = Queue::new();
+            let mut odd: Queue<u32, 5, u8> = Queue::new();
+            let mut ring: Queue<u32, 5, u8, Overwrite> = Queue::new();
+            for item in 0..12 {
+                let _ = try_enqueue(&mut pow2, item);
+                enqueue(&mut odd, item);
+                enqueue_overwrite(&mut ring, item);
+            }
+            assert_eq!(dequeue_pow2(&mut pow2), Some(0));
+            assert_eq!(dequeue(&mut odd), Some(0));
+            assert!(ring.iter().copied().eq(7..12));
+        }
     }
 }
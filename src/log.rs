@@ -0,0 +1,197 @@
+use crate::{CsQueue, Queue};
+use ::log::{LevelFilter, Log, Metadata, Record};
+use core::fmt::{self, Write};
+
+/// Longest line a record is formatted into, including the newline; longer messages are cut
+/// short.
+const LINE_LEN: usize = 128;
+
+/// In-RAM logger: formats each record as a `[LEVEL] target: message` line into a byte ring of
+/// `N` bytes, overwriting the oldest bytes once full. Install it as a `static` with
+/// `log::set_logger` and flush it from a background task or a debugger with
+/// [`Self::drain_to`].
+///
+/// Records are formatted on the stack, outside the critical section, so slow or logging
+/// `Display` impls do not hold off interrupts; only the finished line is copied in under the
+/// lock. Lines are cut at 128 bytes, newline included.
+pub struct LogQueue<const N: usize> {
+    bytes: CsQueue<u8, N>,
+    level: LevelFilter,
+}
+
+/// A record formatted into a stack buffer. Text beyond the buffer is dropped, at a character
+/// boundary so the line stays valid UTF-8.
+struct Line {
+    buffer: [u8; LINE_LEN],
+    len: usize,
+}
+
+impl Line {
+    #[inline(always)]
+    fn format(record: &Record<'_>) -> Self {
+        let mut line = Self {
+            buffer: [0; LINE_LEN],
+            len: 0,
+        };
+        let _ = write!(
+            line,
+            "[{}] {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        line.buffer[line.len] = b'\n';
+        line.len += 1;
+        line
+    }
+
+    #[inline(always)]
+    fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+}
+
+impl Write for Line {
+    #[inline(always)]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // One byte stays free for the newline.
+        let mut fits = s.len().min(LINE_LEN - 1 - self.len);
+        while !s.is_char_boundary(fits) {
+            fits -= 1;
+        }
+        self.buffer[self.len..self.len + fits].copy_from_slice(&s.as_bytes()[..fits]);
+        self.len += fits;
+        Ok(())
+    }
+}
+
+/// Appends `bytes` to the ring, discarding the oldest bytes to make room.
+#[inline(always)]
+fn overwrite<const N: usize>(queue: &mut Queue<u8, N>, bytes: &[u8]) {
+    let bytes = &bytes[bytes.len().saturating_sub(N)..];
+    queue.advance(bytes.len().saturating_sub(queue.remaining_capacity()));
+    queue.enqueue_slice(bytes);
+}
+
+impl<const N: usize> LogQueue<N> {
+    /// Keeps records up to and including `level`.
+    #[inline(always)]
+    pub const fn new(level: LevelFilter) -> Self {
+        Self {
+            bytes: CsQueue::new(),
+            level,
+        }
+    }
+
+    /// Hands the buffered log bytes to `write`, oldest first, in chunks of up to 64 bytes. Each
+    /// chunk is copied out inside a critical section and written outside of it. The oldest line
+    /// may start mid-record if it was partly overwritten.
+    pub fn drain_to(&self, mut write: impl FnMut(&[u8])) {
+        let mut chunk = [0; 64];
+        loop {
+            let len = self.bytes.with(|queue| queue.dequeue_into(&mut chunk));
+            if len == 0 {
+                break;
+            }
+            write(&chunk[..len]);
+        }
+    }
+
+    /// Number of buffered log bytes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.bytes.with(|queue| queue.len())
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.with(|queue| queue.is_empty())
+    }
+}
+
+impl<const N: usize> Log for LogQueue<N> {
+    #[inline(always)]
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = Line::format(record);
+        self.bytes.with(|queue| overwrite(queue, line.as_bytes()));
+    }
+
+    #[inline(always)]
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use ::log::Level;
+    use std::vec::Vec;
+
+    fn log(logger: &impl Log, level: Level, message: fmt::Arguments<'_>) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .target("motor")
+                .args(message)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_formats_and_filters_records() {
+        let logger: LogQueue<64> = LogQueue::new(LevelFilter::Info);
+        let rpm = 4711;
+        log(&logger, Level::Info, format_args!("rpm {rpm}"));
+        log(&logger, Level::Debug, format_args!("ignored"));
+        log(&logger, Level::Warn, format_args!("stall"));
+
+        let mut out = Vec::new();
+        logger.drain_to(|bytes| out.extend_from_slice(bytes));
+        assert_eq!(out, b"[INFO] motor: rpm 4711\n[WARN] motor: stall\n");
+        assert!(logger.is_empty());
+    }
+
+    #[test]
+    fn test_overwrites_oldest_bytes() {
+        let logger: LogQueue<16> = LogQueue::new(LevelFilter::Trace);
+        for step in 0..3 {
+            log(&logger, Level::Error, format_args!("step {step}"));
+        }
+        assert_eq!(logger.len(), 16);
+        let mut out = Vec::new();
+        logger.drain_to(|bytes| out.extend_from_slice(bytes));
+        assert_eq!(out, b"] motor: step 2\n");
+    }
+
+    #[test]
+    fn test_formats_outside_the_critical_section() {
+        static LOGGER: LogQueue<256> = LogQueue::new(LevelFilter::Trace);
+
+        struct Nested;
+
+        impl fmt::Display for Nested {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                log(&LOGGER, Level::Info, format_args!("inner"));
+                f.write_str("outer")
+            }
+        }
+
+        log(&LOGGER, Level::Info, format_args!("{Nested}"));
+        log(&LOGGER, Level::Warn, format_args!("{:µ<200}", ""));
+        let mut out = Vec::new();
+        LOGGER.drain_to(|bytes| out.extend_from_slice(bytes));
+        let text = std::str::from_utf8(&out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[..2], ["[INFO] motor: inner", "[INFO] motor: outer"]);
+        assert_eq!(lines[2].len(), LINE_LEN - 2);
+        assert!(lines[2].starts_with("[WARN] motor: µµ"));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{Iter, Queue, QueueIndex};
+use core::fmt;
+
+/// FIFO queue for best-effort data: [`Self::enqueue`] never fails, and items that do not fit
+/// are discarded and counted instead. Holds `N` elements.
+pub struct LossyQueue<T, const N: usize, I: QueueIndex = usize> {
+    ring: Queue<T, N, I>,
+    dropped: usize,
+}
+
+impl<T, const N: usize, I: QueueIndex> LossyQueue<T, N, I> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            ring: Queue::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Enqueues `item`, or discards and counts it if the queue is full.
+    #[inline(always)]
+    pub fn enqueue(&mut self, item: T) {
+        if self.ring.try_enqueue(item).is_err() {
+            self.dropped = self.dropped.wrapping_add(1);
+        }
+    }
+
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.ring.dequeue()
+    }
+
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.ring.peek()
+    }
+
+    /// Items discarded since creation or the last [`Self::reset_dropped`]. Wraps around on
+    /// overflow.
+    #[inline(always)]
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Restarts the drop counter, returning the count it held.
+    #[inline(always)]
+    pub fn reset_dropped(&mut self) -> usize {
+        core::mem::take(&mut self.dropped)
+    }
+
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.ring.iter()
+    }
+
+    /// Removes the queued elements; the drop counter is kept.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ring.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex> Default for LossyQueue<T, N, I> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize, I: QueueIndex> Clone for LossyQueue<T, N, I> {
+    fn clone(&self) -> Self {
+        Self {
+            ring: self.ring.clone(),
+            dropped: self.dropped,
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, I: QueueIndex> fmt::Debug for LossyQueue<T, N, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LossyQueue")
+            .field("items", &self.ring)
+            .field("dropped", &self.dropped)
+            .finish()
+    }
+}
+
+/// Enqueues every item, counting those that do not fit.
+impl<T, const N: usize, I: QueueIndex> Extend<T> for LossyQueue<T, N, I> {
+    #[inline(always)]
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        for item in iter {
+            self.enqueue(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_dropped_items() {
+        let mut queue: LossyQueue<u32, 3, u8> = LossyQueue::new();
+        queue.extend(0..5);
+        assert_eq!(queue.dropped(), 2);
+        assert!(queue.iter().copied().eq(0..3));
+
+        assert_eq!(queue.dequeue(), Some(0));
+        queue.enqueue(5);
+        queue.enqueue(6);
+        assert_eq!(queue.reset_dropped(), 3);
+        assert_eq!(queue.dropped(), 0);
+        queue.clear();
+        queue.enqueue(7);
+        assert_eq!((queue.len(), queue.dropped()), (1, 0));
+    }
+}
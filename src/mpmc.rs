@@ -0,0 +1,248 @@
+use crate::atomic::{AtomicUsize, Ordering};
+use crate::QueueFull;
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+
+/// Slot of a Vyukov ring, shared with [`MpscQueue`](crate::MpscQueue).
+pub(crate) struct Slot<T> {
+    /// Equals the enqueue position that may claim this slot next, or that position plus one
+    /// once the slot holds an element ready to be dequeued.
+    pub(crate) sequence: AtomicUsize,
+    pub(crate) value: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Slot<T> {
+    const fn new() -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Empty ring, where each slot waits for the enqueue position equal to its index.
+    #[inline(always)]
+    pub(crate) const fn ring<const N: usize>() -> [Self; N] {
+        let mut buffer = [const { Self::new() }; N];
+        let mut i = 0;
+        while i < N {
+            buffer[i].sequence = AtomicUsize::new(i);
+            i += 1;
+        }
+        buffer
+    }
+}
+
+/// Claims the slot at `enqueue_pos` and publishes `item` in it, retrying while other producers
+/// race for the same position. `N` must be a power of 2.
+#[inline(always)]
+pub(crate) fn enqueue<T, const N: usize>(
+    buffer: &[Slot<T>; N],
+    enqueue_pos: &AtomicUsize,
+    item: T,
+) -> Result<(), QueueFull<T>> {
+    let mut pos = enqueue_pos.load(Ordering::Relaxed);
+    loop {
+        let slot = &buffer[pos & (N - 1)];
+        let sequence = slot.sequence.load(Ordering::Acquire);
+        let diff = sequence.wrapping_sub(pos) as isize;
+        if diff == 0 {
+            match enqueue_pos.compare_exchange_weak(
+                pos,
+                pos.wrapping_add(1),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    unsafe { (*slot.value.get()).write(item) };
+                    slot.sequence.store(pos.wrapping_add(1), Ordering::Release);
+                    return Ok(());
+                }
+                Err(current) => pos = current,
+            }
+        } else if diff < 0 {
+            return Err(QueueFull(item));
+        } else {
+            pos = enqueue_pos.load(Ordering::Relaxed);
+        }
+    }
+}
+
+/// Bounded lock-free multi-producer multi-consumer queue, after Dmitry Vyukov's design with a
+/// sequence number per slot. Holds `N` elements; `N` must be a power of 2.
+///
+/// All operations take `&self`, so the queue can be shared through a plain `static`.
+pub struct MpmcQueue<T, const N: usize> {
+    buffer: [Slot<T>; N],
+    enqueue_pos: AtomicUsize,
+    dequeue_pos: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for MpmcQueue<T, N> {}
+
+impl<T, const N: usize> MpmcQueue<T, N> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    /// Positions increase without bound and wrap at `usize::MAX`, which only maps onto the
+    /// buffer consistently when `N` divides the range.
+    const VALID: () = assert!(
+        N.is_power_of_two(),
+        "MpmcQueue capacity N must be a power of 2"
+    );
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        let () = Self::VALID;
+        Self {
+            buffer: Slot::ring(),
+            enqueue_pos: AtomicUsize::new(0),
+            dequeue_pos: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn try_enqueue(&self, item: T) -> Result<(), QueueFull<T>> {
+        enqueue(&self.buffer, &self.enqueue_pos, item)
+    }
+
+    #[inline(always)]
+    pub fn dequeue(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & (N - 1)];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence.wrapping_sub(pos.wrapping_add(1)) as isize;
+            if diff == 0 {
+                match self.dequeue_pos.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let item = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.sequence.store(pos.wrapping_add(N), Ordering::Release);
+                        return Some(item);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                return None;
+            } else {
+                pos = self.dequeue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Number of elements queued. Only a snapshot while other contexts are operating on the
+    /// queue.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        let dequeue_pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let enqueue_pos = self.enqueue_pos.load(Ordering::Relaxed);
+        (enqueue_pos.wrapping_sub(dequeue_pos) as isize).clamp(0, N as isize) as usize
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize> Default for MpmcQueue<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for MpmcQueue<T, N> {
+    fn drop(&mut self) {
+        while self.dequeue().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn test_enqueue_dequeue() {
+        let queue: MpmcQueue<u32, 4> = MpmcQueue::new();
+        assert_eq!(queue.dequeue(), None);
+
+        for round in 0..5 {
+            for i in 0..4 {
+                assert_eq!(queue.try_enqueue(round + i), Ok(()));
+            }
+            assert_eq!(queue.try_enqueue(4711), Err(QueueFull(4711)));
+            assert!(queue.is_full());
+            for i in 0..4 {
+                assert_eq!(queue.dequeue(), Some(round + i));
+            }
+            assert!(queue.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_many_producers_and_consumers() {
+        static QUEUE: MpmcQueue<usize, 8> = MpmcQueue::new();
+        const PER_PRODUCER: usize = 250;
+
+        let total: usize = thread::scope(|s| {
+            for p in 0..4 {
+                s.spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        while QUEUE.try_enqueue(p * PER_PRODUCER + i).is_err() {
+                            thread::yield_now();
+                        }
+                    }
+                });
+            }
+            let consumers: [_; 4] = core::array::from_fn(|_| {
+                s.spawn(|| {
+                    let mut sum = 0;
+                    for _ in 0..PER_PRODUCER {
+                        loop {
+                            if let Some(item) = QUEUE.dequeue() {
+                                sum += item;
+                                break;
+                            }
+                            thread::yield_now();
+                        }
+                    }
+                    sum
+                })
+            });
+            consumers.into_iter().map(|c| c.join().unwrap()).sum()
+        });
+        assert_eq!(total, (0..4 * PER_PRODUCER).sum());
+        assert!(QUEUE.is_empty());
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let queue: MpmcQueue<Rc<()>, 4> = MpmcQueue::new();
+        queue.try_enqueue(token.clone()).unwrap();
+        queue.try_enqueue(token.clone()).unwrap();
+        queue.dequeue();
+        queue.try_enqueue(token.clone()).unwrap();
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+}
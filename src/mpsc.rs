@@ -0,0 +1,245 @@
+use crate::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::mpmc::{self, Slot};
+use crate::QueueFull;
+use core::marker::PhantomData;
+
+/// Bounded lock-free multi-producer single-consumer queue. Holds `N` elements; `N` must be a
+/// power of 2.
+///
+/// Any number of contexts may enqueue through `&self`. Dequeueing goes through the one
+/// [`MpscConsumer`] handed out by [`MpscQueue::consumer`] and never has to retry.
+pub struct MpscQueue<T, const N: usize> {
+    buffer: [Slot<T>; N],
+    enqueue_pos: AtomicUsize,
+    /// Only written by the consumer.
+    dequeue_pos: AtomicUsize,
+    consumer_taken: AtomicBool,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for MpscQueue<T, N> {}
+
+impl<T, const N: usize> MpscQueue<T, N> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    /// Positions increase without bound and wrap at `usize::MAX`, which only maps onto the
+    /// buffer consistently when `N` divides the range.
+    const VALID: () = assert!(
+        N.is_power_of_two(),
+        "MpscQueue capacity N must be a power of 2"
+    );
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        let () = Self::VALID;
+        Self {
+            buffer: Slot::ring(),
+            enqueue_pos: AtomicUsize::new(0),
+            dequeue_pos: AtomicUsize::new(0),
+            consumer_taken: AtomicBool::new(false),
+        }
+    }
+
+    #[inline(always)]
+    pub fn try_enqueue(&self, item: T) -> Result<(), QueueFull<T>> {
+        mpmc::enqueue(&self.buffer, &self.enqueue_pos, item)
+    }
+
+    /// Hands out the consumer, or `None` while another one is alive.
+    #[inline(always)]
+    pub fn consumer(&self) -> Option<MpscConsumer<'_, T, N>> {
+        self.consumer_taken
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        Some(MpscConsumer {
+            queue: self,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Number of elements queued. Only a snapshot while other contexts are operating on the
+    /// queue.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        let dequeue_pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let enqueue_pos = self.enqueue_pos.load(Ordering::Relaxed);
+        (enqueue_pos.wrapping_sub(dequeue_pos) as isize).clamp(0, N as isize) as usize
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// Only the consumer may call this.
+    #[inline(always)]
+    unsafe fn dequeue(&self) -> Option<T> {
+        let pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let slot = &self.buffer[pos & (N - 1)];
+        if slot.sequence.load(Ordering::Acquire) != pos.wrapping_add(1) {
+            return None;
+        }
+        let item = unsafe { (*slot.value.get()).assume_init_read() };
+        slot.sequence.store(pos.wrapping_add(N), Ordering::Release);
+        self.dequeue_pos
+            .store(pos.wrapping_add(1), Ordering::Relaxed);
+        Some(item)
+    }
+
+    /// Only the consumer may call this.
+    #[inline(always)]
+    unsafe fn peek(&self) -> Option<&T> {
+        let pos = self.dequeue_pos.load(Ordering::Relaxed);
+        let slot = &self.buffer[pos & (N - 1)];
+        if slot.sequence.load(Ordering::Acquire) != pos.wrapping_add(1) {
+            return None;
+        }
+        Some(unsafe { (*slot.value.get()).assume_init_ref() })
+    }
+}
+
+impl<T, const N: usize> Default for MpscQueue<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for MpscQueue<T, N> {
+    fn drop(&mut self) {
+        // No consumer can outlive the borrow taken by `consumer`.
+        while unsafe { self.dequeue() }.is_some() {}
+    }
+}
+
+/// Dequeueing half of a [`MpscQueue`]. Dropping it lets [`MpscQueue::consumer`] hand out a new
+/// one.
+pub struct MpscConsumer<'a, T, const N: usize> {
+    queue: &'a MpscQueue<T, N>,
+    /// Keeps the handle `!Sync`, since only one context may dequeue.
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for MpscConsumer<'_, T, N> {}
+
+impl<T, const N: usize> MpscConsumer<'_, T, N> {
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        unsafe { self.queue.dequeue() }
+    }
+
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        unsafe { self.queue.peek() }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl<T, const N: usize> Drop for MpscConsumer<'_, T, N> {
+    fn drop(&mut self) {
+        self.queue.consumer_taken.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn test_enqueue_dequeue() {
+        let queue: MpscQueue<u32, 4> = MpscQueue::new();
+        let mut consumer = queue.consumer().unwrap();
+        assert!(queue.consumer().is_none());
+        assert_eq!(consumer.dequeue(), None);
+
+        for round in 0..5 {
+            for i in 0..4 {
+                assert_eq!(queue.try_enqueue(round + i), Ok(()));
+            }
+            assert_eq!(queue.try_enqueue(4711), Err(QueueFull(4711)));
+            assert!(queue.is_full());
+            assert_eq!(consumer.peek(), Some(&round));
+            for i in 0..4 {
+                assert_eq!(consumer.dequeue(), Some(round + i));
+            }
+            assert!(consumer.is_empty());
+        }
+
+        drop(consumer);
+        assert!(queue.consumer().is_some());
+    }
+
+    #[test]
+    fn test_many_producers() {
+        static QUEUE: MpscQueue<usize, 8> = MpscQueue::new();
+        const PER_PRODUCER: usize = 250;
+
+        let mut consumer = QUEUE.consumer().unwrap();
+        let mut seen = [0usize; 4];
+        thread::scope(|s| {
+            for p in 0..4 {
+                s.spawn(move || {
+                    for i in 0..PER_PRODUCER {
+                        while QUEUE.try_enqueue(p * PER_PRODUCER + i).is_err() {
+                            thread::yield_now();
+                        }
+                    }
+                });
+            }
+            for _ in 0..4 * PER_PRODUCER {
+                loop {
+                    if let Some(item) = consumer.dequeue() {
+                        // Each producer's elements arrive in order.
+                        let p = item / PER_PRODUCER;
+                        assert_eq!(item % PER_PRODUCER, seen[p]);
+                        seen[p] += 1;
+                        break;
+                    }
+                    thread::yield_now();
+                }
+            }
+        });
+        assert_eq!(seen, [PER_PRODUCER; 4]);
+        assert!(QUEUE.is_empty());
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let queue: MpscQueue<Rc<()>, 4> = MpscQueue::new();
+        queue.try_enqueue(token.clone()).unwrap();
+        queue.try_enqueue(token.clone()).unwrap();
+        queue.consumer().unwrap().dequeue();
+        queue.try_enqueue(token.clone()).unwrap();
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+}
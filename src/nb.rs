@@ -0,0 +1,37 @@
+use crate::{Policy, QueueIndex, Storage, StorageQueue};
+use core::convert::Infallible;
+
+/// Non-blocking operations for `nb`-based drivers and `block!` loops.
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Enqueues `item`, or fails with `WouldBlock` while the queue is full. The item is not
+    /// handed back, so this requires `T: Copy` to let the caller retry with the same value.
+    #[inline(always)]
+    pub fn enqueue_nb(&mut self, item: T) -> ::nb::Result<(), Infallible>
+    where
+        T: Copy,
+    {
+        self.try_enqueue(item).map_err(|_| ::nb::Error::WouldBlock)
+    }
+
+    /// Dequeues the oldest element, or fails with `WouldBlock` while the queue is empty.
+    #[inline(always)]
+    pub fn dequeue_nb(&mut self) -> ::nb::Result<T, Infallible> {
+        self.dequeue().ok_or(::nb::Error::WouldBlock)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Queue;
+
+    #[test]
+    fn test_would_block_when_full_or_empty() {
+        let mut queue: Queue<u8, 2> = Queue::new();
+        assert_eq!(queue.dequeue_nb(), Err(::nb::Error::WouldBlock));
+        assert_eq!(::nb::block!(queue.enqueue_nb(1)), Ok(()));
+        assert_eq!(queue.enqueue_nb(2), Ok(()));
+        assert_eq!(queue.enqueue_nb(3), Err(::nb::Error::WouldBlock));
+        assert_eq!(::nb::block!(queue.dequeue_nb()), Ok(1));
+        assert_eq!(queue.dequeue_nb(), Ok(2));
+    }
+}
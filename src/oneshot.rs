@@ -0,0 +1,196 @@
+use crate::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "critical-section")]
+use ::critical_section::Mutex;
+#[cfg(feature = "critical-section")]
+use core::cell::Cell;
+use core::cell::UnsafeCell;
+use core::fmt;
+#[cfg(feature = "critical-section")]
+use core::future::poll_fn;
+use core::mem::MaybeUninit;
+#[cfg(feature = "critical-section")]
+use core::task::{Context, Poll, Waker};
+
+const EMPTY: u8 = 0;
+const WRITING: u8 = 1;
+const FULL: u8 = 2;
+const TAKEN: u8 = 3;
+
+/// Slot that takes exactly one value and hands it out exactly once, e.g. the response to a
+/// request sent to another task along with a `&'static OneShot`. Lock-free and `Sync`, so it
+/// can live in a `static`; [`Self::reset`] makes it ready for the next exchange.
+///
+/// With the `critical-section` feature, [`Self::recv`] waits for the value asynchronously.
+pub struct OneShot<T> {
+    state: AtomicU8,
+    value: UnsafeCell<MaybeUninit<T>>,
+    /// Task waiting in [`Self::recv`].
+    #[cfg(feature = "critical-section")]
+    waker: Mutex<Cell<Option<Waker>>>,
+}
+
+unsafe impl<T: Send> Sync for OneShot<T> {}
+
+impl<T> OneShot<T> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            state: AtomicU8::new(EMPTY),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            #[cfg(feature = "critical-section")]
+            waker: Mutex::new(Cell::new(None)),
+        }
+    }
+
+    /// Stores `value`, or hands it back if a value was already sent.
+    #[inline(always)]
+    pub fn send(&self, value: T) -> Result<(), T> {
+        if self
+            .state
+            .compare_exchange(EMPTY, WRITING, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            return Err(value);
+        }
+        unsafe { (*self.value.get()).write(value) };
+        self.state.store(FULL, Ordering::Release);
+        #[cfg(feature = "critical-section")]
+        if let Some(waker) = ::critical_section::with(|cs| self.waker.borrow(cs).take()) {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Takes the value if it has arrived and nobody took it yet.
+    #[inline(always)]
+    pub fn try_recv(&self) -> Option<T> {
+        self.state
+            .compare_exchange(FULL, TAKEN, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        Some(unsafe { (*self.value.get()).assume_init_read() })
+    }
+
+    /// Waits for the value. Only one task should wait at a time; a second one displaces the
+    /// first, which then waits until it is polled again.
+    #[cfg(feature = "critical-section")]
+    pub async fn recv(&self) -> T {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    #[cfg(feature = "critical-section")]
+    fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<T> {
+        if let Some(value) = self.try_recv() {
+            return Poll::Ready(value);
+        }
+        ::critical_section::with(|cs| self.waker.borrow(cs).set(Some(cx.waker().clone())));
+        // The value may have been sent before the waker was in place.
+        match self.try_recv() {
+            Some(value) => Poll::Ready(value),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Whether a value was sent, whether or not it has been received since.
+    #[inline(always)]
+    pub fn is_sent(&self) -> bool {
+        self.state.load(Ordering::Acquire) >= FULL
+    }
+
+    /// Whether the value was received.
+    #[inline(always)]
+    pub fn is_taken(&self) -> bool {
+        self.state.load(Ordering::Acquire) == TAKEN
+    }
+
+    /// Drops a value that was sent but never received and makes the slot empty again.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        if *self.state.get_mut() == FULL {
+            unsafe { self.value.get_mut().assume_init_drop() };
+        }
+        *self.state.get_mut() = EMPTY;
+    }
+}
+
+impl<T> Default for OneShot<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for OneShot<T> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+impl<T> fmt::Debug for OneShot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OneShot")
+            .field("sent", &self.is_sent())
+            .field("taken", &self.is_taken())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn test_exactly_one_value_once() {
+        let mut reply: OneShot<Rc<u32>> = OneShot::new();
+        assert_eq!(reply.try_recv(), None);
+        assert_eq!(reply.send(Rc::new(4711)), Ok(()));
+        assert_eq!(reply.send(Rc::new(7690)).map_err(|value| *value), Err(7690));
+        assert!(reply.is_sent() && !reply.is_taken());
+        assert_eq!(reply.try_recv().as_deref(), Some(&4711));
+        assert_eq!(reply.try_recv(), None);
+        assert!(reply.is_taken());
+
+        let token = Rc::new(0);
+        reply.reset();
+        reply.send(token.clone()).unwrap();
+        reply.reset();
+        assert_eq!(Rc::strong_count(&token), 1);
+        reply.send(token.clone()).unwrap();
+        drop(reply);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    fn test_request_response_across_threads() {
+        static REPLY: OneShot<u32> = OneShot::new();
+        thread::scope(|s| {
+            s.spawn(|| REPLY.send(4711).unwrap());
+            loop {
+                if let Some(value) = REPLY.try_recv() {
+                    assert_eq!(value, 4711);
+                    break;
+                }
+                thread::yield_now();
+            }
+        });
+    }
+
+    #[cfg(feature = "critical-section")]
+    #[test]
+    fn test_recv_waits_for_send() {
+        use core::future::Future;
+        use core::pin::pin;
+
+        let reply: OneShot<u32> = OneShot::new();
+        let waker = Waker::noop();
+        let mut cx = Context::from_waker(waker);
+        let mut recv = pin!(reply.recv());
+        assert_eq!(recv.as_mut().poll(&mut cx), Poll::Pending);
+        reply.send(4711).unwrap();
+        assert_eq!(recv.as_mut().poll(&mut cx), Poll::Ready(4711));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{Queue, QueueFull};
+use core::mem::{self, MaybeUninit};
+use core::ptr::addr_of;
+
+const MAGIC: u32 = 0x5146_4946;
+const VERSION: u32 = 1;
+
+/// FIFO queue meant to live in a `.noinit` or battery-backed RAM section, so its elements
+/// survive a reset. A magic number, layout version and checksum over the head and length let
+/// [`Self::recover`] tell a surviving queue from garbage. Holds `N` elements.
+///
+/// The checksum is updated after every change, so a reset in the middle of one makes the
+/// queue fail validation and come back empty rather than corrupt.
+pub struct PersistentQueue<T: Copy, const N: usize> {
+    magic: u32,
+    version: u32,
+    checksum: u32,
+    queue: Queue<T, N>,
+}
+
+impl<T: Copy, const N: usize> PersistentQueue<T, N> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            magic: MAGIC,
+            version: VERSION,
+            checksum: Self::checksum(0, 0),
+            queue: Queue::new(),
+        }
+    }
+
+    /// Salted with the capacity and element size, so a firmware update that changes either
+    /// does not restore a queue with a different layout.
+    #[inline(always)]
+    const fn checksum(head: usize, len: usize) -> u32 {
+        let salt = (N as u32).rotate_left(16) ^ mem::size_of::<T>() as u32;
+        (head as u32).wrapping_mul(0x9E37_79B9).rotate_left(13) ^ (len as u32) ^ salt ^ MAGIC
+    }
+
+    /// Validates the queue left in `slot` by a previous run and restores it, or reinitializes
+    /// it to an empty queue. Returns the queue and whether it was restored.
+    ///
+    /// # Safety
+    ///
+    /// Every bit pattern must be a valid `T`, e.g. integers or arrays of them, since restored
+    /// elements are read from whatever the memory holds.
+    pub unsafe fn recover(slot: &mut MaybeUninit<Self>) -> (&mut Self, bool) {
+        let this = slot.as_ptr();
+        let (magic, version, checksum, head, len) = unsafe {
+            (
+                addr_of!((*this).magic).read_volatile(),
+                addr_of!((*this).version).read_volatile(),
+                addr_of!((*this).checksum).read_volatile(),
+                addr_of!((*this).queue.head).read_volatile(),
+                addr_of!((*this).queue.len).read_volatile(),
+            )
+        };
+        let restored = magic == MAGIC
+            && version == VERSION
+            && head < N
+            && len <= N
+            && checksum == Self::checksum(head, len);
+        if !restored {
+            slot.write(Self::new());
+        }
+        let this = unsafe { slot.assume_init_mut() };
+        #[cfg(feature = "stats")]
+        this.queue.reset_stats();
+        (this, restored)
+    }
+
+    #[inline(always)]
+    fn seal(&mut self) {
+        self.checksum = Self::checksum(self.queue.head(), self.queue.len());
+    }
+
+    #[inline(always)]
+    pub fn enqueue(&mut self, item: T) {
+        self.queue.enqueue(item);
+        self.seal();
+    }
+
+    #[inline(always)]
+    pub fn try_enqueue(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        self.queue.try_enqueue(item)?;
+        self.seal();
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        let item = self.queue.dequeue()?;
+        self.seal();
+        Some(item)
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.seal();
+    }
+
+    /// The queued elements, for everything that does not modify them.
+    #[inline(always)]
+    pub fn queue(&self) -> &Queue<T, N> {
+        &self.queue
+    }
+}
+
+impl<T: Copy, const N: usize> Default for PersistentQueue<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_survives_reset() {
+        let mut ram = MaybeUninit::<PersistentQueue<u32, 4>>::zeroed();
+        let (queue, restored) = unsafe { PersistentQueue::recover(&mut ram) };
+        assert!(!restored);
+        assert!(queue.queue().is_empty());
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.enqueue(63460);
+        assert_eq!(queue.dequeue(), Some(26769));
+
+        let (queue, restored) = unsafe { PersistentQueue::recover(&mut ram) };
+        assert!(restored);
+        assert!(queue.queue().iter().copied().eq([64004, 63460]));
+    }
+
+    #[test]
+    fn test_reinitializes_when_corrupt() {
+        let mut ram = MaybeUninit::new(PersistentQueue::<u32, 4>::new());
+        let (queue, _) = unsafe { PersistentQueue::recover(&mut ram) };
+        queue.enqueue(26769);
+        queue.queue.len = 2;
+
+        let (queue, restored) = unsafe { PersistentQueue::recover(&mut ram) };
+        assert!(!restored);
+        assert!(queue.queue().is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use core::fmt;
+
+/// Double buffer of two `N`-element halves: one is being filled, e.g. by DMA, while the other
+/// is being drained by the CPU, and [`Self::swap`] exchanges their roles. The halves are
+/// contiguous, first half first, as circular DMA in half-transfer mode expects.
+pub struct PingPong<T, const N: usize> {
+    halves: [[T; N]; 2],
+    fill: usize,
+}
+
+impl<T, const N: usize> PingPong<T, N> {
+    /// Starts with both halves set to `value` and the first half as the fill side.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self
+    where
+        T: Copy,
+    {
+        Self {
+            halves: [[value; N]; 2],
+            fill: 0,
+        }
+    }
+
+    /// The half currently being filled.
+    #[inline(always)]
+    pub fn fill_side(&mut self) -> &mut [T; N] {
+        &mut self.halves[self.fill]
+    }
+
+    /// The half currently being drained, i.e. the one filled before the last swap.
+    #[inline(always)]
+    pub fn drain_side(&self) -> &[T; N] {
+        &self.halves[1 - self.fill]
+    }
+
+    #[inline(always)]
+    pub fn drain_side_mut(&mut self) -> &mut [T; N] {
+        &mut self.halves[1 - self.fill]
+    }
+
+    /// Both halves at once, fill side first.
+    #[inline(always)]
+    pub fn sides_mut(&mut self) -> (&mut [T; N], &mut [T; N]) {
+        let [first, second] = &mut self.halves;
+        if self.fill == 0 {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    /// Makes the freshly filled half the drain side and vice versa.
+    #[inline(always)]
+    pub fn swap(&mut self) {
+        self.fill = 1 - self.fill;
+    }
+
+    /// Index, 0 or 1, of the half currently being filled.
+    #[inline(always)]
+    pub fn fill_half(&self) -> usize {
+        self.fill
+    }
+
+    /// Both halves as one buffer, first half first, regardless of their roles.
+    #[inline(always)]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.halves.as_flattened_mut()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for PingPong<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for PingPong<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PingPong")
+            .field("fill", &self.halves[self.fill])
+            .field("drain", self.drain_side())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_halves_alternate() {
+        let mut buffer: PingPong<u16, 3> = PingPong::new(0);
+        assert_eq!(buffer.fill_half(), 0);
+
+        for round in 0..4 {
+            buffer.fill_side().fill(round);
+            buffer.swap();
+            assert_eq!(buffer.drain_side(), &[round; 3]);
+            let (fill, drain) = buffer.sides_mut();
+            fill.copy_from_slice(drain);
+            fill[0] += 100;
+            buffer.swap();
+            assert_eq!(buffer.drain_side(), &[round + 100, round, round]);
+        }
+        assert_eq!(buffer.fill_half(), 0);
+        assert_eq!(buffer.as_mut_slice(), [3, 3, 3, 103, 3, 3]);
+    }
+}
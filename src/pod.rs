@@ -0,0 +1,136 @@
+use crate::{QueueFull, QueueRef};
+use ::bytemuck::{Pod, PodCastError};
+use core::mem::MaybeUninit;
+
+/// FIFO queue whose storage is a caller-provided byte region, e.g. a linker-placed buffer in a
+/// specific SRAM bank. Holds as many elements as the region fits.
+pub struct PodQueue<'a, T: Pod> {
+    ring: QueueRef<'a, T>,
+}
+
+impl<'a, T: Pod> PodQueue<'a, T> {
+    /// Takes over `bytes` as storage. Fails if `bytes` is not aligned for `T` or its length is
+    /// not a multiple of `T`'s size.
+    #[inline(always)]
+    pub fn from_bytes(bytes: &'a mut [u8]) -> Result<Self, PodCastError> {
+        let buffer: &mut [T] = ::bytemuck::try_cast_slice_mut(bytes)?;
+        // The ring only ever writes initialized `T`s, so the region stays valid for `T` afterwards.
+        let buffer = unsafe { &mut *(buffer as *mut [T] as *mut [MaybeUninit<T>]) };
+        Ok(Self {
+            ring: QueueRef::new(buffer),
+        })
+    }
+
+    #[inline(always)]
+    pub fn enqueue(&mut self, item: T) {
+        self.ring.enqueue(item);
+    }
+
+    #[inline(always)]
+    pub fn try_enqueue(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        self.ring.try_enqueue(item)
+    }
+
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.ring.dequeue()
+    }
+
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.ring.peek()
+    }
+
+    /// Returns the `n`-th oldest element, where `get(0)` is the front.
+    #[inline(always)]
+    pub fn get(&self, n: usize) -> Option<&T> {
+        self.ring.get(n)
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ring.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C, align(4))]
+    struct Region([u8; 17]);
+
+    #[test]
+    fn test_queue_over_byte_region() {
+        let mut region = Region([0; 17]);
+        let mut queue: PodQueue<u32> = PodQueue::from_bytes(&mut region.0[..12]).unwrap();
+        assert_eq!(queue.capacity(), 3);
+
+        for round in 0..5 {
+            assert_eq!(queue.try_enqueue(round), Ok(()));
+            assert_eq!(queue.try_enqueue(round + 1), Ok(()));
+            assert_eq!(queue.try_enqueue(round + 2), Ok(()));
+            assert_eq!(queue.try_enqueue(4711), Err(QueueFull(4711)));
+            assert_eq!(queue.get(1), Some(&(round + 1)));
+            assert_eq!(queue.dequeue(), Some(round));
+            assert_eq!(queue.dequeue(), Some(round + 1));
+            assert_eq!(queue.dequeue(), Some(round + 2));
+            assert!(queue.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_rejects_misaligned_or_ragged_region() {
+        let mut region = Region([0; 17]);
+        assert!(matches!(
+            PodQueue::<u32>::from_bytes(&mut region.0[1..9]),
+            Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned)
+        ));
+        assert!(matches!(
+            PodQueue::<u32>::from_bytes(&mut region.0[..17]),
+            Err(PodCastError::OutputSliceWouldHaveSlop)
+        ));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_wipes_byte_region() {
+        fn raw(queue: &PodQueue<u32>) -> [u32; 2] {
+            core::array::from_fn(|i| unsafe { queue.ring.buffer[i].assume_init() })
+        }
+
+        let mut region = Region([0xff; 17]);
+        let mut queue: PodQueue<u32> = PodQueue::from_bytes(&mut region.0[..8]).unwrap();
+        queue.enqueue(26769);
+        queue.enqueue(64004);
+        queue.dequeue();
+        assert_eq!(raw(&queue), [0, 64004]);
+        queue.enqueue(63460);
+        queue.clear();
+        assert_eq!(raw(&queue), [0, 0]);
+        queue.enqueue(857);
+        drop(queue);
+        assert_eq!(region.0[..8], [0; 8]);
+        assert_eq!(region.0[8..], [0xff; 9]);
+    }
+}
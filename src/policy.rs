@@ -0,0 +1,38 @@
+/// What [`Queue::enqueue`](crate::Queue::enqueue) does with an item that arrives while the queue
+/// is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnFull {
+    /// Discard the new item.
+    Reject,
+    /// Evict the oldest element to make room.
+    Overwrite,
+    /// Panic.
+    Panic,
+}
+
+/// Fixes the full-queue behavior of [`Queue::enqueue`](crate::Queue::enqueue) in the queue's
+/// type, e.g. `Queue<T, 16, usize, Overwrite>`.
+pub trait Policy {
+    const ON_FULL: OnFull;
+}
+
+/// Full queues discard new items. The default.
+pub struct Reject;
+
+/// Full queues evict their oldest element.
+pub struct Overwrite;
+
+/// Full queues panic.
+pub struct Panic;
+
+impl Policy for Reject {
+    const ON_FULL: OnFull = OnFull::Reject;
+}
+
+impl Policy for Overwrite {
+    const ON_FULL: OnFull = OnFull::Overwrite;
+}
+
+impl Policy for Panic {
+    const ON_FULL: OnFull = OnFull::Panic;
+}
@@ -0,0 +1,125 @@
+use crate::{Queue, QueueFull};
+use core::fmt;
+
+/// Two FIFO lanes behind one consumer: high-priority items jump ahead of everything in the low
+/// lane, while each lane keeps its own order. Holds `HI` high and `LO` low-priority elements.
+pub struct PrioQueue<T, const HI: usize, const LO: usize> {
+    high: Queue<T, HI>,
+    low: Queue<T, LO>,
+}
+
+impl<T, const HI: usize, const LO: usize> PrioQueue<T, HI, LO> {
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            high: Queue::new(),
+            low: Queue::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn enqueue_high(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        self.high.try_enqueue(item)
+    }
+
+    #[inline(always)]
+    pub fn enqueue_low(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        self.low.try_enqueue(item)
+    }
+
+    /// Dequeues the oldest high-priority element, or the oldest low-priority one if the high
+    /// lane is empty.
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.high.dequeue().or_else(|| self.low.dequeue())
+    }
+
+    /// Returns the element [`Self::dequeue`] would return next.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.high.peek().or_else(|| self.low.peek())
+    }
+
+    /// The high-priority lane, oldest first.
+    #[inline(always)]
+    pub fn high(&self) -> &Queue<T, HI> {
+        &self.high
+    }
+
+    /// The low-priority lane, oldest first.
+    #[inline(always)]
+    pub fn low(&self) -> &Queue<T, LO> {
+        &self.low
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.high.clear();
+        self.low.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.high.is_empty() && self.low.is_empty()
+    }
+
+    /// Number of elements in both lanes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.high.len() + self.low.len()
+    }
+}
+
+impl<T, const HI: usize, const LO: usize> Default for PrioQueue<T, HI, LO> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const HI: usize, const LO: usize> Clone for PrioQueue<T, HI, LO> {
+    fn clone(&self) -> Self {
+        Self {
+            high: self.high.clone(),
+            low: self.low.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const HI: usize, const LO: usize> fmt::Debug for PrioQueue<T, HI, LO> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrioQueue")
+            .field("high", &self.high)
+            .field("low", &self.low)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_high_jumps_ahead_of_low() {
+        let mut queue: PrioQueue<u32, 2, 4> = PrioQueue::new();
+        assert_eq!(queue.dequeue(), None);
+
+        for round in 0..5 {
+            assert_eq!(queue.enqueue_low(round), Ok(()));
+            assert_eq!(queue.enqueue_low(round + 1), Ok(()));
+            assert_eq!(queue.enqueue_high(round + 100), Ok(()));
+            assert_eq!(queue.enqueue_high(round + 101), Ok(()));
+            assert_eq!(queue.enqueue_high(4711), Err(QueueFull(4711)));
+            assert_eq!(queue.len(), 4);
+
+            assert_eq!(queue.peek(), Some(&(round + 100)));
+            assert_eq!(queue.dequeue(), Some(round + 100));
+            assert_eq!(queue.enqueue_low(round + 2), Ok(()));
+            assert_eq!(queue.dequeue(), Some(round + 101));
+            assert_eq!(queue.dequeue(), Some(round));
+            assert_eq!(queue.dequeue(), Some(round + 1));
+            assert_eq!(queue.dequeue(), Some(round + 2));
+            assert!(queue.is_empty());
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Reject, StorageQueue};
+use core::mem::MaybeUninit;
+
+/// FIFO queue over borrowed storage, so the capacity is chosen at runtime, e.g. by carving
+/// several queues out of one static arena with `split_at_mut`. Holds `buffer.len()` elements.
+pub type QueueRef<'a, T, I = usize, P = Reject> = StorageQueue<T, &'a mut [MaybeUninit<T>], I, P>;
+
+impl<'a, T> QueueRef<'a, T> {
+    /// Takes over `buffer` as storage; whatever it holds is treated as free. Other index
+    /// types and policies go through [`StorageQueue::from_storage`].
+    #[inline(always)]
+    pub const fn new(buffer: &'a mut [MaybeUninit<T>]) -> Self {
+        Self::from_storage(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::QueueFull;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_queues_carved_from_one_arena() {
+        let mut arena = [const { MaybeUninit::<u32>::uninit() }; 8];
+        let (left, right) = arena.split_at_mut(3);
+        let mut small = QueueRef::new(left);
+        let mut large = QueueRef::new(right);
+        assert_eq!((small.capacity(), large.capacity()), (3, 5));
+
+        for round in 0..5 {
+            for i in 0..3 {
+                assert_eq!(small.try_enqueue(round + i), Ok(()));
+            }
+            assert_eq!(small.try_enqueue(4711), Err(QueueFull(4711)));
+            while let Some(item) = small.dequeue() {
+                large.enqueue(item);
+            }
+            assert!(large.iter().copied().eq(round..round + 3));
+            large.clear();
+        }
+    }
+
+    #[test]
+    fn test_as_slices_and_drop() {
+        let token = Rc::new(());
+        let mut arena = [const { MaybeUninit::<Rc<()>>::uninit() }; 3];
+        let mut queue = QueueRef::new(&mut arena[..]);
+        for _ in 0..3 {
+            queue.enqueue(token.clone());
+        }
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue(token.clone());
+        assert_eq!(queue.as_slices().0.len(), 1);
+        assert_eq!(queue.as_slices().1.len(), 1);
+        assert_eq!(queue.back().map(Rc::strong_count), Some(3));
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    fn test_raw_parts_round_trip() {
+        let token = Rc::new(());
+        let mut arena = [const { MaybeUninit::<Rc<()>>::uninit() }; 3];
+        let mut queue = QueueRef::new(&mut arena[..]);
+        for _ in 0..3 {
+            queue.enqueue(token.clone());
+        }
+        queue.dequeue();
+        queue.enqueue(token.clone());
+
+        let (ptr, capacity, head, len) = queue.into_raw_parts();
+        assert_eq!((capacity, head, len), (3, 1, 3));
+        assert_eq!(Rc::strong_count(&token), 4);
+        let mut queue = unsafe { QueueRef::from_raw_parts(ptr, capacity, head, len) };
+        assert_eq!(
+            queue.dequeue().map(|item| Rc::ptr_eq(&item, &token)),
+            Some(true)
+        );
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_wipes_borrowed_slots() {
+        fn raw(slots: &[MaybeUninit<u8>]) -> [u8; 4] {
+            core::array::from_fn(|i| unsafe { slots[i].assume_init() })
+        }
+
+        let mut arena = [MaybeUninit::new(0xff); 4];
+        let mut queue = QueueRef::new(&mut arena[..]);
+        queue.extend([1, 2, 3, 4]);
+        queue.dequeue();
+        assert_eq!(raw(queue.buffer), [0, 2, 3, 4]);
+        queue.truncate(2);
+        assert_eq!(raw(queue.buffer), [0, 2, 3, 0]);
+        queue.clear();
+        assert_eq!(raw(queue.buffer), [0; 4]);
+        queue.extend([5, 6]);
+        drop(queue);
+        assert_eq!(raw(&arena), [0; 4]);
+    }
+}
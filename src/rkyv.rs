@@ -0,0 +1,92 @@
+use crate::{OwnedStorage, Policy, QueueFull, QueueIndex, Storage, StorageQueue};
+use ::rkyv::rancor::{Fallible, Source};
+use ::rkyv::ser::{Allocator, Writer};
+use ::rkyv::vec::{ArchivedVec, VecResolver};
+use ::rkyv::{Archive, Archived, Deserialize, Place, Serialize};
+
+/// Archives the live elements, oldest first, as an [`ArchivedVec`], so a checkpoint can be
+/// read in place once mapped back from flash.
+impl<T: Archive, S: Storage<T>, I: QueueIndex, P: Policy> Archive for StorageQueue<T, S, I, P> {
+    type Archived = ArchivedVec<Archived<T>>;
+    type Resolver = VecResolver;
+
+    #[inline(always)]
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<T, S, I, P, Ser> Serialize<Ser> for StorageQueue<T, S, I, P>
+where
+    T: Serialize<Ser>,
+    S: Storage<T>,
+    I: QueueIndex,
+    P: Policy,
+    Ser: Fallible + Allocator + Writer + ?Sized,
+{
+    #[inline(always)]
+    fn serialize(&self, serializer: &mut Ser) -> Result<Self::Resolver, Ser::Error> {
+        ArchivedVec::serialize_from_iter::<T, _, _>(self.iter(), serializer)
+    }
+}
+
+/// Fails with [`QueueFull`] if the archive holds more than [`OwnedStorage::MAX_CAPACITY`]
+/// elements. Storage that can be allocated at any size gets exactly as many slots as there are.
+impl<T, S, I, P, D> Deserialize<StorageQueue<T, S, I, P>, D> for ArchivedVec<Archived<T>>
+where
+    T: Archive,
+    Archived<T>: Deserialize<T, D>,
+    S: OwnedStorage<T>,
+    I: QueueIndex,
+    P: Policy,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<StorageQueue<T, S, I, P>, D::Error> {
+        if self.len() > S::MAX_CAPACITY {
+            return Err(D::Error::new(QueueFull(())));
+        }
+        let mut queue = StorageQueue::from_storage(S::with_capacity(self.len()));
+        for item in self.iter() {
+            let _ = queue.try_enqueue(item.deserialize(deserializer)?);
+        }
+        Ok(queue)
+    }
+}
+
+impl<T, U, S, I, P> PartialEq<StorageQueue<U, S, I, P>> for ArchivedVec<T>
+where
+    T: PartialEq<U>,
+    S: Storage<U>,
+    I: QueueIndex,
+    P: Policy,
+{
+    #[inline(always)]
+    fn eq(&self, other: &StorageQueue<U, S, I, P>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Queue;
+    use ::rkyv::rancor::Error;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let mut queue: Queue<u32, 4, u8> = Queue::from_array([26769, 64004, 63460]);
+        queue.dequeue();
+        queue.enqueue(857);
+        queue.enqueue(4711);
+
+        let bytes = ::rkyv::to_bytes::<Error>(&queue).unwrap();
+        let archived = ::rkyv::access::<ArchivedVec<Archived<u32>>, Error>(&bytes).unwrap();
+        assert_eq!(archived, &queue);
+        assert_eq!(archived.as_slice(), [64004, 63460, 857, 4711]);
+
+        let restored: Queue<u32, 4> = ::rkyv::deserialize::<_, Error>(archived).unwrap();
+        assert_eq!(restored, queue);
+        assert!(::rkyv::deserialize::<Queue<u32, 3>, Error>(archived).is_err());
+    }
+}
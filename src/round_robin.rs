@@ -0,0 +1,146 @@
+#[cfg(feature = "critical-section")]
+use crate::CsQueue;
+use crate::{Consumer, MpmcQueue, MpscConsumer, Policy, QueueIndex, Storage, StorageQueue};
+
+/// Anything a [`RoundRobin`] can take elements from: a queue it may borrow mutably, or the
+/// consuming handle of a split one. Shared queues implement it on `&` references, so that a
+/// `static` can take part.
+pub trait Source<T> {
+    fn dequeue(&mut self) -> Option<T>;
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> Source<T> for StorageQueue<T, S, I, P> {
+    #[inline(always)]
+    fn dequeue(&mut self) -> Option<T> {
+        StorageQueue::dequeue(self)
+    }
+}
+
+impl<T, const N: usize> Source<T> for Consumer<'_, T, N> {
+    #[inline(always)]
+    fn dequeue(&mut self) -> Option<T> {
+        Consumer::dequeue(self)
+    }
+}
+
+impl<T, const N: usize> Source<T> for MpscConsumer<'_, T, N> {
+    #[inline(always)]
+    fn dequeue(&mut self) -> Option<T> {
+        MpscConsumer::dequeue(self)
+    }
+}
+
+impl<T, const N: usize> Source<T> for &MpmcQueue<T, N> {
+    #[inline(always)]
+    fn dequeue(&mut self) -> Option<T> {
+        MpmcQueue::dequeue(self)
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<T, const N: usize> Source<T> for &CsQueue<T, N> {
+    #[inline(always)]
+    fn dequeue(&mut self) -> Option<T> {
+        CsQueue::dequeue(self)
+    }
+}
+
+/// Takes elements from `K` sources in turn, so a busy source cannot starve the others. Each
+/// call starts at the source after the one that last yielded an element and skips empty ones.
+///
+/// As an [`Iterator`] it yields elements until every source is empty.
+pub struct RoundRobin<'a, T, const K: usize> {
+    sources: [&'a mut dyn Source<T>; K],
+    next: usize,
+}
+
+impl<'a, T, const K: usize> RoundRobin<'a, T, K> {
+    /// Starts with `sources[0]`.
+    #[inline(always)]
+    pub fn new(sources: [&'a mut dyn Source<T>; K]) -> Self {
+        Self { sources, next: 0 }
+    }
+
+    /// Dequeues from the next non-empty source, or returns `None` if all are empty.
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.dequeue_indexed().map(|(_, item)| item)
+    }
+
+    /// Like [`Self::dequeue`], also returning the index of the source the element came from.
+    pub fn dequeue_indexed(&mut self) -> Option<(usize, T)> {
+        for offset in 0..K {
+            let index = (self.next + offset) % K;
+            if let Some(item) = self.sources[index].dequeue() {
+                self.next = (index + 1) % K;
+                return Some((index, item));
+            }
+        }
+        None
+    }
+
+    /// Index of the source that is asked first on the next call.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.next
+    }
+
+    #[inline(always)]
+    pub fn into_inner(self) -> [&'a mut dyn Source<T>; K] {
+        self.sources
+    }
+}
+
+impl<T, const K: usize> Iterator for RoundRobin<'_, T, K> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T> {
+        self.dequeue()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{Queue, SpscQueue};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_alternates_between_sources() {
+        let mut uart: Queue<u32, 8> = Queue::from_array([10, 11, 12, 13]);
+        let mut can: Queue<u32, 4, u8> = Queue::from_array([20]);
+        let mut spsc: SpscQueue<u32, 4> = SpscQueue::new();
+        let (mut producer, mut consumer) = spsc.split();
+        producer.try_enqueue(30).unwrap();
+        producer.try_enqueue(31).unwrap();
+
+        let mut mux = RoundRobin::new([&mut uart, &mut can, &mut consumer]);
+        assert_eq!(mux.dequeue_indexed(), Some((0, 10)));
+        assert_eq!(mux.position(), 1);
+        let rest: Vec<u32> = mux.by_ref().collect();
+        assert_eq!(rest, [20, 30, 11, 31, 12, 13]);
+        assert_eq!(mux.position(), 1);
+
+        producer.try_enqueue(32).unwrap();
+        assert_eq!(mux.dequeue_indexed(), Some((2, 32)));
+        assert_eq!(mux.position(), 0);
+    }
+
+    #[test]
+    fn test_shared_and_empty_sources() {
+        static SHARED: MpmcQueue<u8, 4> = MpmcQueue::new();
+        SHARED.try_enqueue(1).unwrap();
+        SHARED.try_enqueue(2).unwrap();
+        let mut shared = &SHARED;
+        let mut idle: Queue<u8, 2> = Queue::new();
+        let mut mux = RoundRobin::new([&mut idle, &mut shared]);
+        assert!(mux.by_ref().eq([1, 2]));
+        assert_eq!(mux.dequeue(), None);
+
+        let mut none: RoundRobin<'_, u8, 0> = RoundRobin::new([]);
+        assert_eq!(none.dequeue(), None);
+    }
+}
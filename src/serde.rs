@@ -0,0 +1,142 @@
+use crate::{OwnedStorage, Policy, QueueIndex, Storage, StorageQueue};
+use ::serde::de::{Deserialize, Deserializer, Error, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, SerializeSeq, Serializer};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// Serializes the live elements as a sequence, oldest first.
+impl<T: Serialize, S: Storage<T>, I: QueueIndex, P: Policy> Serialize for StorageQueue<T, S, I, P> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for item in self.iter() {
+            seq.serialize_element(item)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserializes a sequence into a queue, failing if it holds more than
+/// [`OwnedStorage::MAX_CAPACITY`] elements. Storage that can be allocated at any size starts
+/// out as large as the sequence claims to be and grows when it turns out longer.
+impl<'de, T, S, I, P> Deserialize<'de> for StorageQueue<T, S, I, P>
+where
+    T: Deserialize<'de>,
+    S: OwnedStorage<T>,
+    I: QueueIndex,
+    P: Policy,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct QueueVisitor<T, S, I, P>(PhantomData<(T, S, I, P)>);
+
+        impl<'de, T, S, I, P> Visitor<'de> for QueueVisitor<T, S, I, P>
+        where
+            T: Deserialize<'de>,
+            S: OwnedStorage<T>,
+            I: QueueIndex,
+            P: Policy,
+        {
+            type Value = StorageQueue<T, S, I, P>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match S::MAX_CAPACITY {
+                    usize::MAX => f.write_str("a sequence"),
+                    capacity => write!(f, "a sequence of at most {capacity} elements"),
+                }
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let hint = seq.size_hint().unwrap_or(0).min(S::MAX_CAPACITY);
+                let mut queue = StorageQueue::from_storage(S::with_capacity(hint));
+                while let Some(item) = seq.next_element()? {
+                    if queue.is_full() && queue.capacity() < S::MAX_CAPACITY {
+                        let capacity = queue.capacity().saturating_mul(2).max(4);
+                        let mut grown = StorageQueue::from_storage(S::with_capacity(capacity));
+                        grown.append(&mut queue);
+                        queue = grown;
+                    }
+                    if queue.try_enqueue(item).is_err() {
+                        return Err(A::Error::invalid_length(queue.len() + 1, &self));
+                    }
+                }
+                Ok(queue)
+            }
+        }
+
+        deserializer.deserialize_seq(QueueVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use crate::BoxedQueue;
+    use crate::Queue;
+    #[cfg(feature = "alloc")]
+    use serde_test::assert_de_tokens;
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+    #[test]
+    fn test_serde_round_trip_live_elements() {
+        let mut queue: Queue<u32, 4> = Queue::new();
+        queue.enqueue(26769);
+        queue.dequeue();
+        queue.enqueue(63460);
+        queue.enqueue(857);
+
+        assert_tokens(
+            &queue,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U32(63460),
+                Token::U32(857),
+                Token::SeqEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_deserialize_rejects_overflow() {
+        assert_de_tokens_error::<Queue<u32, 4>>(
+            &[
+                Token::Seq { len: Some(5) },
+                Token::U32(1),
+                Token::U32(2),
+                Token::U32(3),
+                Token::U32(4),
+                Token::U32(5),
+            ],
+            "invalid length 5, expected a sequence of at most 4 elements",
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_deserialize_boxed_without_length() {
+        let mut queue: BoxedQueue<u32> = BoxedQueue::with_capacity(5);
+        queue.extend([1, 2, 3, 4, 5]);
+        assert_tokens(
+            &queue,
+            &[
+                Token::Seq { len: Some(5) },
+                Token::U32(1),
+                Token::U32(2),
+                Token::U32(3),
+                Token::U32(4),
+                Token::U32(5),
+                Token::SeqEnd,
+            ],
+        );
+        assert_de_tokens(
+            &queue,
+            &[
+                Token::Seq { len: None },
+                Token::U32(1),
+                Token::U32(2),
+                Token::U32(3),
+                Token::U32(4),
+                Token::U32(5),
+                Token::SeqEnd,
+            ],
+        );
+    }
+}
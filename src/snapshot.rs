@@ -0,0 +1,152 @@
+use crate::{wrap_at, Iter, OwnedStorage, Policy, QueueIndex, Storage, StorageQueue};
+use core::fmt;
+use core::mem::MaybeUninit;
+
+/// Copy of a queue's state taken by [`StorageQueue::snapshot`]: the live elements and where
+/// the head was, so [`StorageQueue::restore`] can rewind a queue to the exact same layout.
+pub struct QueueSnapshot<T, S: Storage<T>> {
+    head: usize,
+    items: StorageQueue<T, S>,
+}
+
+impl<T, S: Storage<T>> QueueSnapshot<T, S> {
+    /// The captured elements, front first.
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl<T: Clone, S: OwnedStorage<T>> Clone for QueueSnapshot<T, S> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head,
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq, S: Storage<T>> PartialEq for QueueSnapshot<T, S> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.head == other.head && self.items == other.items
+    }
+}
+
+impl<T: Eq, S: Storage<T>> Eq for QueueSnapshot<T, S> {}
+
+impl<T: fmt::Debug, S: Storage<T>> fmt::Debug for QueueSnapshot<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueueSnapshot")
+            .field("head", &self.head)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+impl<T: Clone, S: Storage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Clones the live elements and the head position into a [`QueueSnapshot`].
+    #[inline(always)]
+    pub fn snapshot(&self) -> QueueSnapshot<T, S>
+    where
+        S: OwnedStorage<T>,
+    {
+        let mut items = StorageQueue::from_storage(self.buffer.new_like());
+        items.extend(self.iter().cloned());
+        QueueSnapshot {
+            head: self.head(),
+            items,
+        }
+    }
+
+    /// Drops the current elements and puts clones of the snapshot's elements back in the slots
+    /// they occupied, so the head, length and wrap-around point match the snapshot. The same
+    /// snapshot can be restored any number of times. Statistics are left as they are.
+    ///
+    /// # Panics
+    ///
+    /// If the snapshot was taken from a queue of a different capacity.
+    pub fn restore<R: Storage<T>>(&mut self, snap: &QueueSnapshot<T, R>) {
+        assert_eq!(
+            snap.items.capacity(),
+            self.capacity(),
+            "snapshot taken from a queue of a different capacity"
+        );
+        self.clear();
+        self.head = I::from_usize(snap.head);
+        let capacity = self.capacity();
+        for (n, item) in snap.items.iter().enumerate() {
+            self.slots_mut()[wrap_at(snap.head + n, capacity)] = MaybeUninit::new(item.clone());
+            self.len = I::from_usize(n + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Queue;
+
+    #[test]
+    fn test_restore_rewinds_layout() {
+        let mut queue: Queue<u32, 4, u8> = Queue::from_array([26769, 64004, 63460]);
+        queue.dequeue();
+        queue.enqueue(857);
+        queue.enqueue(4711);
+        let snap = queue.snapshot();
+        assert!(snap.iter().copied().eq([64004, 63460, 857, 4711]));
+
+        for _ in 0..2 {
+            queue.clear();
+            queue.enqueue(1);
+            queue.restore(&snap);
+            assert_eq!(queue.as_slices(), (&[64004, 63460, 857][..], &[4711][..]));
+        }
+    }
+
+    #[test]
+    fn test_restore_drops_current_elements() {
+        extern crate std;
+        use std::rc::Rc;
+
+        let shared = Rc::new(());
+        let mut queue: Queue<Rc<()>, 3> = Queue::new();
+        let snap = queue.snapshot();
+        queue.enqueue(shared.clone());
+        queue.enqueue(shared.clone());
+        queue.restore(&snap);
+        assert!(queue.is_empty() && snap.is_empty());
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_restore_boxed_queue() {
+        let mut queue = crate::BoxedQueue::with_capacity(3);
+        queue.extend([1, 2, 3]);
+        queue.dequeue();
+        queue.enqueue(4);
+        let snap = queue.snapshot();
+        queue.clear();
+        queue.restore(&snap);
+        assert_eq!(queue.as_slices(), (&[2, 3][..], &[4][..]));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[should_panic(expected = "snapshot taken from a queue of a different capacity")]
+    fn test_restore_rejects_other_capacity() {
+        let snap = crate::BoxedQueue::<u32>::with_capacity(3).snapshot();
+        crate::BoxedQueue::with_capacity(4).restore(&snap);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::atomic::{AtomicUsize, Ordering};
+use crate::validate::check_position;
+use crate::{Corruption, QueueFull};
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+/// Lock-free single-producer single-consumer queue. Holds `N` elements.
+///
+/// [`SpscQueue::split`] hands out a [`Producer`] and a [`Consumer`] that can live in different
+/// contexts, e.g. an interrupt handler and the main loop, without a critical section.
+pub struct SpscQueue<T, const N: usize> {
+    buffer: [UnsafeCell<MaybeUninit<T>>; N],
+    /// Positions run over `0..2 * N` so that a full queue can be told apart from an empty one.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send, const N: usize> Sync for SpscQueue<T, N> {}
+
+/// Advances a position in `0..2 * N` by `n <= N`.
+#[inline(always)]
+fn advance<const N: usize>(position: usize, n: usize) -> usize {
+    let position = position + n;
+    if position >= 2 * N {
+        position - 2 * N
+    } else {
+        position
+    }
+}
+
+/// Buffer index of a position in `0..2 * N`.
+#[inline(always)]
+fn index<const N: usize>(position: usize) -> usize {
+    if position >= N {
+        position - N
+    } else {
+        position
+    }
+}
+
+#[inline(always)]
+fn distance<const N: usize>(head: usize, tail: usize) -> usize {
+    if tail >= head {
+        tail - head
+    } else {
+        tail + 2 * N - head
+    }
+}
+
+impl<T, const N: usize> SpscQueue<T, N> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    const VALID: () = assert!(N > 0, "queue capacity N must be at least 1");
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        let () = Self::VALID;
+        Self {
+            buffer: [const { UnsafeCell::new(MaybeUninit::uninit()) }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        (
+            Producer {
+                queue: self,
+                _marker: PhantomData,
+            },
+            Consumer {
+                queue: self,
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        distance::<N>(head, tail)
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// Checks that both positions are in range and at most `N` apart.
+    pub fn debug_validate(&self) -> Result<(), Corruption> {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        check_position("head", head, 2 * N)?;
+        check_position("tail", tail, 2 * N)?;
+        let len = distance::<N>(head, tail);
+        if len > N {
+            return Err(Corruption::LenOutOfRange { len, capacity: N });
+        }
+        Ok(())
+    }
+
+    /// Only the producer may call this.
+    #[inline(always)]
+    unsafe fn try_enqueue(&self, item: T) -> Result<(), QueueFull<T>> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if distance::<N>(head, tail) == N {
+            return Err(QueueFull(item));
+        }
+        unsafe { (*self.buffer[index::<N>(tail)].get()).write(item) };
+        self.tail.store(advance::<N>(tail, 1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Only the consumer may call this.
+    #[inline(always)]
+    unsafe fn dequeue(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let item = unsafe { (*self.buffer[index::<N>(head)].get()).assume_init_read() };
+        self.head.store(advance::<N>(head, 1), Ordering::Release);
+        Some(item)
+    }
+
+    /// Only the consumer may call this.
+    #[inline(always)]
+    unsafe fn peek(&self) -> Option<&T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        Some(unsafe { (*self.buffer[index::<N>(head)].get()).assume_init_ref() })
+    }
+}
+
+impl<T, const N: usize> Default for SpscQueue<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for SpscQueue<T, N> {
+    fn drop(&mut self) {
+        // No handles can outlive the `&mut self` borrow taken by `split`.
+        while unsafe { self.dequeue() }.is_some() {}
+    }
+}
+
+/// Enqueueing half of a [`SpscQueue`].
+pub struct Producer<'a, T, const N: usize> {
+    queue: &'a SpscQueue<T, N>,
+    /// Keeps the handle `!Sync`, since only one context may enqueue.
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for Producer<'_, T, N> {}
+
+impl<T, const N: usize> Producer<'_, T, N> {
+    #[inline(always)]
+    pub fn try_enqueue(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        unsafe { self.queue.try_enqueue(item) }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.queue.is_full()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+/// Dequeueing half of a [`SpscQueue`].
+pub struct Consumer<'a, T, const N: usize> {
+    queue: &'a SpscQueue<T, N>,
+    /// Keeps the handle `!Sync`, since only one context may dequeue.
+    _marker: PhantomData<*const ()>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for Consumer<'_, T, N> {}
+
+impl<T, const N: usize> Consumer<'_, T, N> {
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<T> {
+        unsafe { self.queue.dequeue() }
+    }
+
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        unsafe { self.queue.peek() }
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+    use std::thread;
+
+    #[test]
+    fn test_split_enqueue_dequeue() {
+        let mut queue: SpscQueue<u32, 3> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+        assert_eq!(consumer.dequeue(), None);
+
+        for round in 0..5 {
+            assert_eq!(producer.try_enqueue(round), Ok(()));
+            assert_eq!(producer.try_enqueue(round + 1), Ok(()));
+            assert_eq!(producer.try_enqueue(round + 2), Ok(()));
+            assert_eq!(producer.try_enqueue(4711), Err(QueueFull(4711)));
+            assert!(producer.is_full());
+            assert_eq!(consumer.peek(), Some(&round));
+            assert_eq!(consumer.dequeue(), Some(round));
+            assert_eq!(consumer.dequeue(), Some(round + 1));
+            assert_eq!(consumer.dequeue(), Some(round + 2));
+            assert!(consumer.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_across_threads() {
+        let mut queue: SpscQueue<u32, 4> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+        thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..1000 {
+                    while producer.try_enqueue(i).is_err() {
+                        thread::yield_now();
+                    }
+                }
+            });
+            for i in 0..1000 {
+                loop {
+                    if let Some(item) = consumer.dequeue() {
+                        assert_eq!(item, i);
+                        break;
+                    }
+                    thread::yield_now();
+                }
+            }
+        });
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let mut queue: SpscQueue<Rc<()>, 4> = SpscQueue::new();
+        let (mut producer, _) = queue.split();
+        producer.try_enqueue(token.clone()).unwrap();
+        producer.try_enqueue(token.clone()).unwrap();
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[test]
+    fn test_zero_sized_tokens() {
+        let mut queue: SpscQueue<(), 2> = SpscQueue::new();
+        let (mut producer, mut consumer) = queue.split();
+        for _ in 0..3 {
+            assert_eq!(producer.try_enqueue(()), Ok(()));
+            assert_eq!(producer.try_enqueue(()), Ok(()));
+            assert_eq!(producer.try_enqueue(()), Err(QueueFull(())));
+            assert_eq!(consumer.dequeue(), Some(()));
+            assert_eq!(consumer.dequeue(), Some(()));
+            assert_eq!(consumer.dequeue(), None);
+        }
+    }
+
+    #[test]
+    fn test_debug_validate() {
+        let queue: SpscQueue<u32, 4> = SpscQueue::new();
+        assert_eq!(queue.debug_validate(), Ok(()));
+        queue.tail.store(5, Ordering::Relaxed);
+        assert_eq!(
+            queue.debug_validate(),
+            Err(Corruption::LenOutOfRange {
+                len: 5,
+                capacity: 4
+            })
+        );
+        queue.tail.store(8, Ordering::Relaxed);
+        assert!(queue.debug_validate().is_err());
+        queue.tail.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{Iter, Queue, QueueFull, QueueIndex};
+use core::fmt;
+
+/// Statically allocated LIFO stack. Holds `N` elements.
+///
+/// Shares [`Queue`]'s storage; since elements only come and go at the back, the ring never wraps
+/// and the elements stay contiguous.
+pub struct Stack<T, const N: usize, I: QueueIndex = usize> {
+    ring: Queue<T, N, I>,
+}
+
+impl<T, const N: usize, I: QueueIndex> Stack<T, N, I> {
+    /// Number of elements the stack can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { ring: Queue::new() }
+    }
+
+    #[inline(always)]
+    pub fn push(&mut self, item: T) -> Result<(), QueueFull<T>> {
+        self.ring.try_enqueue(item)
+    }
+
+    #[inline(always)]
+    pub fn pop(&mut self) -> Option<T> {
+        self.ring.take_back()
+    }
+
+    /// Returns the top element.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<&T> {
+        self.ring.back()
+    }
+
+    #[inline(always)]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.ring.back_mut()
+    }
+
+    /// The elements from bottom to top.
+    #[inline(always)]
+    pub fn as_slice(&self) -> &[T] {
+        self.ring.as_slices().0
+    }
+
+    /// Iterates from bottom to top.
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.ring.iter()
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ring.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex> Default for Stack<T, N, I> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize, I: QueueIndex> Clone for Stack<T, N, I> {
+    fn clone(&self) -> Self {
+        Self {
+            ring: self.ring.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, I: QueueIndex> fmt::Debug for Stack<T, N, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_push_pop_peek() {
+        let mut stack: Stack<u32, 3> = Stack::new();
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.peek(), None);
+
+        for round in 0..5 {
+            assert_eq!(stack.push(round), Ok(()));
+            assert_eq!(stack.push(round + 1), Ok(()));
+            assert_eq!(stack.push(round + 2), Ok(()));
+            assert_eq!(stack.push(4711), Err(QueueFull(4711)));
+            assert_eq!(stack.peek(), Some(&(round + 2)));
+            assert_eq!(stack.as_slice(), [round, round + 1, round + 2]);
+            assert_eq!(stack.pop(), Some(round + 2));
+            assert_eq!(stack.pop(), Some(round + 1));
+            assert_eq!(stack.pop(), Some(round));
+            assert!(stack.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_drop_releases_live_elements() {
+        let token = Rc::new(());
+        let mut stack: Stack<Rc<()>, 4> = Stack::new();
+        stack.push(token.clone()).unwrap();
+        stack.push(token.clone()).unwrap();
+        drop(stack.pop());
+        assert_eq!(Rc::strong_count(&token), 2);
+        drop(stack);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+}
@@ -0,0 +1,150 @@
+/// Number of buckets in [`Stats::occupancy`].
+pub const OCCUPANCY_BUCKETS: usize = 8;
+
+/// Counters a [`Queue`](crate::Queue) keeps when the `stats` feature is enabled, e.g. to
+/// right-size its capacity. Counts wrap around on overflow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// Highest number of elements held at once.
+    pub high_water: usize,
+    /// Elements accepted by any enqueue method.
+    pub enqueued: usize,
+    /// Elements that left the queue by any means, e.g. `dequeue`, `advance`, `remove`,
+    /// `truncate` or an eviction, so the queue holds `enqueued - dequeued` elements.
+    pub dequeued: usize,
+    /// Elements refused because the queue was full.
+    pub rejected: usize,
+    /// Elements evicted by `enqueue_overwrite`, which also count as dequeued.
+    pub overwritten: usize,
+    /// Depth sampled after each enqueue call that accepted elements: bucket `i` counts the
+    /// calls that left the queue more than `i` and at most `i + 1` eighths full.
+    pub occupancy: [usize; OCCUPANCY_BUCKETS],
+}
+
+impl Stats {
+    #[inline(always)]
+    pub(crate) const fn new() -> Self {
+        Self {
+            high_water: 0,
+            enqueued: 0,
+            dequeued: 0,
+            rejected: 0,
+            overwritten: 0,
+            occupancy: [0; OCCUPANCY_BUCKETS],
+        }
+    }
+
+    /// Records `n` accepted elements, leaving a queue of capacity `capacity` holding `len`.
+    #[inline(always)]
+    pub(crate) const fn enqueued(&mut self, n: usize, len: usize, capacity: usize) {
+        self.enqueued = self.enqueued.wrapping_add(n);
+        if len > self.high_water {
+            self.high_water = len;
+        }
+        if n > 0 {
+            let bucket = (len * OCCUPANCY_BUCKETS - 1) / capacity;
+            // Always true as `len <= capacity`, but spelled out so the hot path keeps no panic branch.
+            if bucket < OCCUPANCY_BUCKETS {
+                self.occupancy[bucket] = self.occupancy[bucket].wrapping_add(1);
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) const fn dequeued(&mut self, n: usize) {
+        self.dequeued = self.dequeued.wrapping_add(n);
+    }
+
+    #[inline(always)]
+    pub(crate) const fn rejected(&mut self, n: usize) {
+        self.rejected = self.rejected.wrapping_add(n);
+    }
+
+    #[inline(always)]
+    pub(crate) const fn overwritten(&mut self) {
+        self.overwritten = self.overwritten.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Queue;
+
+    #[test]
+    fn test_counts_operations() {
+        let mut queue: Queue<u8, 4> = Queue::new();
+        queue.enqueue(1);
+        queue.enqueue(2);
+        assert_eq!(queue.enqueue_slice(&[3, 4, 5]), 2);
+        assert!(queue.try_enqueue(6).is_err());
+        assert_eq!(queue.enqueue_overwrite(7), Some(1));
+        assert_eq!(queue.dequeue(), Some(2));
+        let mut out = [0; 2];
+        assert_eq!(queue.dequeue_into(&mut out), 2);
+
+        assert_eq!(
+            queue.stats(),
+            Stats {
+                high_water: 4,
+                enqueued: 5,
+                dequeued: 4,
+                rejected: 2,
+                overwritten: 1,
+                occupancy: [0, 1, 0, 1, 0, 0, 0, 2],
+            }
+        );
+
+        queue.reset_stats();
+        assert_eq!(
+            queue.stats(),
+            Stats {
+                high_water: 1,
+                ..Stats::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_every_removal_is_counted() {
+        let mut queue: Queue<u32, 8> = Queue::new();
+        let balanced = |queue: &Queue<u32, 8>| {
+            let stats = queue.stats();
+            stats.enqueued - stats.dequeued == queue.len()
+        };
+        queue.extend(0..8);
+        assert_eq!(queue.advance(3), 3);
+        assert_eq!(queue.dequeue(), Some(3));
+        assert!(balanced(&queue));
+
+        queue.extend(8..12);
+        assert_eq!(queue.remove(1), Some(5));
+        assert_eq!(queue.swap_remove_back(0), Some(4));
+        queue.retain(|&item| item % 2 == 0);
+        assert!(queue.iter().copied().eq([6, 8, 10]));
+        assert!(balanced(&queue));
+
+        queue.enqueue_overwrite(12);
+        queue.truncate(1);
+        assert!(balanced(&queue));
+        queue.clear();
+        assert_eq!(queue.stats().dequeued, queue.stats().enqueued);
+    }
+
+    #[test]
+    fn test_occupancy_histogram() {
+        let mut queue: Queue<u8, 64> = Queue::new();
+        for round in 0..10 {
+            queue.enqueue(round);
+            queue.enqueue(round);
+            queue.dequeue();
+        }
+        assert_eq!(queue.occupancy_histogram(), [15, 5, 0, 0, 0, 0, 0, 0]);
+        queue.extend(0..54);
+        assert_eq!(queue.enqueue_slice(&[0; 8]), 0);
+        assert_eq!(queue.occupancy_histogram(), [15, 11, 8, 8, 8, 8, 8, 8]);
+
+        queue.reset_stats();
+        assert_eq!(queue.occupancy_histogram(), [0; OCCUPANCY_BUCKETS]);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::validate::check_ring;
+use crate::{Policy, Queue, QueueIndex, StorageQueue};
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::{ptr, slice};
+
+/// Backing memory for a [`StorageQueue`]: an inline array, a borrowed slice such as a
+/// `&'static mut` buffer, or with the `alloc` feature a boxed slice.
+///
+/// # Safety
+///
+/// Both methods must return the same memory, with the same length, for as long as the value
+/// lives, since the queue keeps elements in it between calls.
+pub const unsafe trait Storage<T> {
+    fn as_slice(&self) -> &[MaybeUninit<T>];
+
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>];
+}
+
+unsafe impl<T, const N: usize> const Storage<T> for [MaybeUninit<T>; N] {
+    #[inline(always)]
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+unsafe impl<T> const Storage<T> for &mut [MaybeUninit<T>] {
+    #[inline(always)]
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+unsafe impl<T> Storage<T> for Box<[MaybeUninit<T>]> {
+    #[inline(always)]
+    fn as_slice(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    #[inline(always)]
+    fn as_mut_slice(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+/// [`Storage`] a queue can allocate for itself, so the methods that hand back a new queue, e.g.
+/// [`StorageQueue::split_off`] and [`StorageQueue::chunks`], work over it. Borrowed slices
+/// cannot.
+pub trait OwnedStorage<T>: Storage<T> + Sized {
+    /// Most slots [`Self::with_capacity`] can provide.
+    const MAX_CAPACITY: usize;
+
+    /// Uninitialized storage with `capacity` slots, or as close as the type allows: an array
+    /// always has its own length.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Uninitialized storage with as many slots as `self`.
+    #[inline(always)]
+    fn new_like(&self) -> Self {
+        Self::with_capacity(self.as_slice().len())
+    }
+}
+
+impl<T, const N: usize> OwnedStorage<T> for [MaybeUninit<T>; N] {
+    const MAX_CAPACITY: usize = N;
+
+    #[inline(always)]
+    fn with_capacity(_capacity: usize) -> Self {
+        [const { MaybeUninit::uninit() }; N]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> OwnedStorage<T> for Box<[MaybeUninit<T>]> {
+    const MAX_CAPACITY: usize = usize::MAX;
+
+    #[inline(always)]
+    fn with_capacity(capacity: usize) -> Self {
+        Box::new_uninit_slice(capacity)
+    }
+}
+
+impl<T> StorageQueue<T, &mut [MaybeUninit<T>]> {
+    /// Releases the queue without dropping its elements, returning the storage pointer, the
+    /// capacity, the head and the length. The elements stay in place for
+    /// [`Self::from_raw_parts`] to pick up, e.g. after a jump from a bootloader or on another
+    /// core sharing the memory.
+    #[inline(always)]
+    pub fn into_raw_parts(self) -> (*mut MaybeUninit<T>, usize, usize, usize) {
+        let mut this = ManuallyDrop::new(self);
+        let (head, len) = (this.head(), this.len());
+        let slots = this.slots_mut();
+        (slots.as_mut_ptr(), slots.len(), head, len)
+    }
+
+    /// Rebuilds a queue from the parts returned by [`Self::into_raw_parts`]. The length is
+    /// passed rather than a tail index, since a tail equal to the head could mean either empty
+    /// or full. Statistics start over, with the high-water mark at `len`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads and writes of `capacity` slots while the queue lives, and
+    /// not be accessed through anything else meanwhile. `head` must be below `capacity`, or
+    /// zero if that is zero, and `len` must not exceed `capacity`. The `len` slots from `head`
+    /// on, wrapping around at `capacity`, must hold initialized elements, which the queue takes
+    /// ownership of.
+    #[inline(always)]
+    pub unsafe fn from_raw_parts(
+        ptr: *mut MaybeUninit<T>,
+        capacity: usize,
+        head: usize,
+        len: usize,
+    ) -> Self {
+        debug_assert!(check_ring(head, len, capacity).is_ok());
+        let mut queue = Self::from_storage(unsafe { slice::from_raw_parts_mut(ptr, capacity) });
+        queue.head = head;
+        queue.len = len;
+        #[cfg(feature = "stats")]
+        {
+            queue.stats.high_water = len;
+        }
+        queue
+    }
+}
+
+impl<T, const N: usize, I: QueueIndex, P: Policy> Queue<T, N, I, P> {
+    /// Releases the queue without dropping its elements, returning the buffer, the head and the
+    /// length. The `len` elements from slot `head` on, wrapping around at `N`, are initialized;
+    /// the other slots are not. Placing the parts in memory that survives a reset, e.g. a
+    /// `.noinit` section shared with a bootloader, lets [`Self::from_raw_parts`] pick the queue
+    /// up again.
+    #[inline(always)]
+    pub fn into_raw_parts(self) -> ([MaybeUninit<T>; N], usize, usize) {
+        let this = ManuallyDrop::new(self);
+        // `this` is never dropped, so the elements move out with the buffer exactly once.
+        (unsafe { ptr::read(&this.buffer) }, this.head(), this.len())
+    }
+
+    /// Rebuilds a queue from the parts returned by [`Self::into_raw_parts`]. Statistics start
+    /// over, with the high-water mark at `len`.
+    ///
+    /// # Safety
+    ///
+    /// `head` must be below `N` and `len` must not exceed `N`. The `len` slots from `head` on,
+    /// wrapping around at `N`, must hold initialized elements, which the queue takes ownership
+    /// of.
+    #[inline(always)]
+    pub unsafe fn from_raw_parts(buffer: [MaybeUninit<T>; N], head: usize, len: usize) -> Self {
+        debug_assert!(check_ring(head, len, N).is_ok());
+        let mut queue = Self::new();
+        queue.buffer = buffer;
+        queue.head = I::from_usize(head);
+        queue.len = I::from_usize(len);
+        #[cfg(feature = "stats")]
+        {
+            queue.stats.high_water = len;
+        }
+        queue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::QueueRef;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_inline_and_borrowed_storage_agree() {
+        let mut inline: Queue<u32, 5> = Queue::new();
+        let mut arena = [const { MaybeUninit::<u32>::uninit() }; 5];
+        let mut borrowed = QueueRef::new(&mut arena[..]);
+
+        for round in 0..10 {
+            inline.extend([round, round + 1, round + 2]);
+            borrowed.extend([round, round + 1, round + 2]);
+            assert_eq!(inline, borrowed);
+            let mut oldest = [0; 2];
+            assert_eq!(inline.dequeue_into(&mut oldest), 2);
+            assert_eq!(borrowed.dequeue(), Some(oldest[0]));
+            assert_eq!(borrowed.dequeue(), Some(oldest[1]));
+            assert_eq!(inline.back_mut(), borrowed.back_mut());
+        }
+    }
+
+    #[test]
+    fn test_inline_raw_parts_round_trip() {
+        let token = Rc::new(());
+        let mut queue: Queue<Rc<()>, 3, u8> = Queue::new();
+        for _ in 0..3 {
+            queue.enqueue(token.clone());
+        }
+        queue.dequeue();
+        queue.dequeue();
+        queue.enqueue(token.clone());
+
+        let (buffer, head, len) = queue.into_raw_parts();
+        assert_eq!((head, len), (2, 2));
+        assert_eq!(Rc::strong_count(&token), 3);
+        let mut queue: Queue<Rc<()>, 3, u8> = unsafe { Queue::from_raw_parts(buffer, head, len) };
+        assert_eq!(queue.as_slices().1.len(), 1);
+        assert_eq!(
+            queue.dequeue().map(|item| Rc::ptr_eq(&item, &token)),
+            Some(true)
+        );
+        drop(queue);
+        assert_eq!(Rc::strong_count(&token), 1);
+    }
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn test_raw_parts_restore_high_water() {
+        let (buffer, head, len) = Queue::<u8, 4>::from_array([1, 2, 3]).into_raw_parts();
+        let inline: Queue<u8, 4> = unsafe { Queue::from_raw_parts(buffer, head, len) };
+        let mut arena = [MaybeUninit::new(0u8); 4];
+        let borrowed = unsafe { QueueRef::from_raw_parts(arena.as_mut_ptr(), 4, 1, 3) };
+        assert_eq!(inline.stats().high_water, 3);
+        assert_eq!(borrowed.stats().high_water, 3);
+    }
+
+    #[test]
+    fn test_custom_storage() {
+        #[repr(align(64))]
+        struct CacheLine([MaybeUninit<u16>; 6]);
+
+        unsafe impl Storage<u16> for CacheLine {
+            fn as_slice(&self) -> &[MaybeUninit<u16>] {
+                &self.0
+            }
+
+            fn as_mut_slice(&mut self) -> &mut [MaybeUninit<u16>] {
+                &mut self.0
+            }
+        }
+
+        let mut queue: StorageQueue<u16, CacheLine> =
+            StorageQueue::from_storage(CacheLine([MaybeUninit::uninit(); 6]));
+        queue.extend(0..8);
+        assert!(queue.is_full());
+        assert_eq!(queue.dequeue(), Some(0));
+        queue.enqueue(6);
+        assert!(queue.iter().copied().eq(1..7));
+        let (_, wrapped) = queue.as_slices();
+        assert_eq!(wrapped, [6]);
+        assert_eq!(wrapped.as_ptr() as usize % 64, 0);
+    }
+}
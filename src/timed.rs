@@ -0,0 +1,140 @@
+use crate::{Queue, QueueFull};
+use core::fmt;
+
+/// FIFO queue that stamps every element with the tick it was enqueued at, so stale elements
+/// can be discarded instead of acted upon late. Holds `N` elements.
+///
+/// Ages are computed with wrapping arithmetic, so a free-running tick counter may overflow as
+/// long as no element is kept for more than `u32::MAX` ticks.
+pub struct TimedQueue<T, const N: usize> {
+    ring: Queue<(u32, T), N>,
+}
+
+impl<T, const N: usize> TimedQueue<T, N> {
+    /// Number of elements the queue can hold.
+    pub const CAPACITY: usize = N;
+
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { ring: Queue::new() }
+    }
+
+    /// Enqueues `item` stamped with `now`. Ticks must not go backwards between calls.
+    #[inline(always)]
+    pub fn enqueue(&mut self, now: u32, item: T) -> Result<(), QueueFull<T>> {
+        self.ring
+            .try_enqueue((now, item))
+            .map_err(|QueueFull((_, item))| QueueFull(item))
+    }
+
+    /// Dequeues the oldest element together with its tick, however old it is.
+    #[inline(always)]
+    pub fn dequeue(&mut self) -> Option<(u32, T)> {
+        self.ring.dequeue()
+    }
+
+    /// Discards every element older than `max_age` ticks at `now`, then dequeues the oldest
+    /// remaining one.
+    #[inline(always)]
+    pub fn dequeue_fresh(&mut self, now: u32, max_age: u32) -> Option<T> {
+        self.purge_expired(now, max_age);
+        self.ring.dequeue().map(|(_, item)| item)
+    }
+
+    /// Discards every element older than `max_age` ticks at `now`, returning how many were
+    /// discarded.
+    #[inline(always)]
+    pub fn purge_expired(&mut self, now: u32, max_age: u32) -> usize {
+        let expired = self
+            .ring
+            .iter()
+            .take_while(|(tick, _)| now.wrapping_sub(*tick) > max_age)
+            .count();
+        self.ring.advance(expired)
+    }
+
+    /// The oldest element and its tick.
+    #[inline(always)]
+    pub fn peek(&self) -> Option<(u32, &T)> {
+        self.ring.peek().map(|(tick, item)| (*tick, item))
+    }
+
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.ring.clear();
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.ring.is_full()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.ring.len()
+    }
+
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+impl<T, const N: usize> Default for TimedQueue<T, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for TimedQueue<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            ring: self.ring.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for TimedQueue<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.ring.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_elements_are_discarded() {
+        let mut queue: TimedQueue<u32, 4> = TimedQueue::new();
+        assert_eq!(queue.dequeue_fresh(0, 10), None);
+
+        assert_eq!(queue.enqueue(100, 26769), Ok(()));
+        assert_eq!(queue.enqueue(105, 64004), Ok(()));
+        assert_eq!(queue.enqueue(110, 63460), Ok(()));
+        assert_eq!(queue.enqueue(112, 857), Ok(()));
+        assert_eq!(queue.enqueue(113, 4711), Err(QueueFull(4711)));
+
+        assert_eq!(queue.purge_expired(110, 10), 0);
+        assert_eq!(queue.dequeue_fresh(116, 10), Some(63460));
+        assert_eq!(queue.peek(), Some((112, &857)));
+        assert_eq!(queue.purge_expired(200, 10), 1);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_ages_survive_tick_wraparound() {
+        let mut queue: TimedQueue<u32, 4> = TimedQueue::new();
+        queue.enqueue(u32::MAX - 5, 26769).unwrap();
+        queue.enqueue(u32::MAX, 64004).unwrap();
+        queue.enqueue(3, 63460).unwrap();
+        assert_eq!(queue.dequeue_fresh(4, 6), Some(64004));
+        assert_eq!(queue.dequeue(), Some((3, 63460)));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{Policy, QueueIndex, Storage, StorageQueue};
+use core::fmt;
+
+/// Broken invariant found by one of the `debug_validate` methods, a sign that the queue's
+/// header was overwritten, e.g. by a stray pointer or a stack overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corruption {
+    /// The position named by `field` is not below `limit`.
+    PositionOutOfRange {
+        field: &'static str,
+        value: usize,
+        limit: usize,
+    },
+    /// The queue claims to hold more elements than fit.
+    LenOutOfRange { len: usize, capacity: usize },
+    /// The readable bytes of a [`BipBuffer`](crate::BipBuffer) run into the free region.
+    RegionsOverlap {
+        read: usize,
+        write: usize,
+        last: usize,
+    },
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::PositionOutOfRange {
+                field,
+                value,
+                limit,
+            } => write!(f, "{field} is {value}, must be below {limit}"),
+            Self::LenOutOfRange { len, capacity } => {
+                write!(f, "length {len} exceeds capacity {capacity}")
+            }
+            Self::RegionsOverlap { read, write, last } => write!(
+                f,
+                "read region overlaps free region (read {read}, write {write}, last {last})"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Corruption {}
+
+#[inline(always)]
+pub(crate) fn check_position(
+    field: &'static str,
+    value: usize,
+    limit: usize,
+) -> Result<(), Corruption> {
+    if value < limit {
+        Ok(())
+    } else {
+        Err(Corruption::PositionOutOfRange {
+            field,
+            value,
+            limit,
+        })
+    }
+}
+
+#[inline(always)]
+pub(crate) fn check_ring(head: usize, len: usize, capacity: usize) -> Result<(), Corruption> {
+    // An empty storage has nowhere for the head to point, so zero is its only valid value.
+    if head != 0 {
+        check_position("head", head, capacity)?;
+    }
+    if len > capacity {
+        return Err(Corruption::LenOutOfRange { len, capacity });
+    }
+    Ok(())
+}
+
+impl<T, S: Storage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Checks that the head and length are in range. Cheap enough to call from a watchdog task
+    /// to catch a corrupted queue before it hands out garbage.
+    #[inline(always)]
+    pub fn debug_validate(&self) -> Result<(), Corruption> {
+        check_ring(self.head(), self.len(), self.capacity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::Queue;
+    use std::string::ToString;
+
+    #[test]
+    fn test_detects_corrupt_header() {
+        let mut queue: Queue<u32, 4, u8> = Queue::from_array([26769, 64004]);
+        assert_eq!(queue.debug_validate(), Ok(()));
+        queue.head = 4;
+        let corruption = queue.debug_validate().unwrap_err();
+        assert_eq!(corruption.to_string(), "head is 4, must be below 4");
+        queue.head = 0;
+        queue.len = 5;
+        assert_eq!(
+            queue.debug_validate(),
+            Err(Corruption::LenOutOfRange {
+                len: 5,
+                capacity: 4
+            })
+        );
+        queue.len = 0;
+    }
+}
@@ -0,0 +1,118 @@
+use core::marker::PhantomPinned;
+use core::ptr;
+use core::task::Waker;
+
+/// Node of a [`WaiterList`], stored in the future of the task that waits. It is linked while
+/// that future is pending and unlinked before the future completes or is dropped, so the list
+/// never points at a node that has gone away.
+pub(crate) struct Waiter {
+    waker: Option<Waker>,
+    /// Elements or free slots the task needs before it can make progress.
+    need: usize,
+    prev: *mut Waiter,
+    next: *mut Waiter,
+    linked: bool,
+    /// Other nodes point at this one, so it must not move while linked.
+    _pin: PhantomPinned,
+}
+
+/// The links are only followed inside the critical section guarding the list.
+unsafe impl Send for Waiter {}
+
+impl Waiter {
+    #[inline(always)]
+    pub(crate) const fn new(need: usize) -> Self {
+        Self {
+            waker: None,
+            need,
+            prev: ptr::null_mut(),
+            next: ptr::null_mut(),
+            linked: false,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+/// Intrusive FIFO of waiting tasks, so any number of them can wait without allocating. Tasks
+/// are woken one at a time, oldest first among those whose need can be met.
+pub(crate) struct WaiterList {
+    head: *mut Waiter,
+    tail: *mut Waiter,
+}
+
+unsafe impl Send for WaiterList {}
+
+impl WaiterList {
+    #[inline(always)]
+    pub(crate) const fn new() -> Self {
+        Self {
+            head: ptr::null_mut(),
+            tail: ptr::null_mut(),
+        }
+    }
+
+    /// Stores `waker` in `node` and appends the node unless it is already waiting.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be valid and stay in place until it is passed to [`Self::remove`], and no
+    /// other reference to it may be live during the call.
+    pub(crate) unsafe fn register(&mut self, node: *mut Waiter, waker: &Waker) {
+        let waiter = unsafe { &mut *node };
+        match &waiter.waker {
+            Some(registered) if registered.will_wake(waker) => {}
+            _ => waiter.waker = Some(waker.clone()),
+        }
+        if waiter.linked {
+            return;
+        }
+        waiter.linked = true;
+        waiter.prev = self.tail;
+        waiter.next = ptr::null_mut();
+        match self.tail.is_null() {
+            true => self.head = node,
+            false => unsafe { (*self.tail).next = node },
+        }
+        self.tail = node;
+    }
+
+    /// Unlinks `node` if it is still waiting.
+    ///
+    /// # Safety
+    ///
+    /// `node` must be valid and either unlinked or linked into this list, and no other
+    /// reference to it may be live during the call.
+    pub(crate) unsafe fn remove(&mut self, node: *mut Waiter) {
+        let waiter = unsafe { &mut *node };
+        if !waiter.linked {
+            return;
+        }
+        match waiter.prev.is_null() {
+            true => self.head = waiter.next,
+            false => unsafe { (*waiter.prev).next = waiter.next },
+        }
+        match waiter.next.is_null() {
+            true => self.tail = waiter.prev,
+            false => unsafe { (*waiter.next).prev = waiter.prev },
+        }
+        waiter.linked = false;
+    }
+
+    /// Unlinks the oldest waiter needing at most `available` and returns its waker, to be woken
+    /// after the critical section.
+    #[inline(always)]
+    pub(crate) fn pop(&mut self, available: usize) -> Option<Waker> {
+        let mut node = self.head;
+        // Linked nodes are valid until removed, which only happens under the same lock.
+        unsafe {
+            while !node.is_null() {
+                if (*node).need <= available {
+                    self.remove(node);
+                    return (*node).waker.take();
+                }
+                node = (*node).next;
+            }
+        }
+        None
+    }
+}
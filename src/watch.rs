@@ -0,0 +1,145 @@
+use ::critical_section::Mutex;
+use core::cell::RefCell;
+use core::fmt;
+
+/// Single slot holding the latest value sent, for channels where only the most recent sample
+/// matters, e.g. a sensor reading handed from an interrupt to a task. Sending overwrites the
+/// previous value and bumps a generation counter, so readers can tell whether anything new
+/// arrived since they last looked.
+pub struct Watch<T> {
+    slot: Mutex<RefCell<Slot<T>>>,
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    generation: usize,
+}
+
+impl<T> Watch<T> {
+    /// Starts empty at generation 0.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            slot: Mutex::new(RefCell::new(Slot {
+                value: None,
+                generation: 0,
+            })),
+        }
+    }
+
+    #[inline(always)]
+    fn lock<R>(&self, f: impl FnOnce(&mut Slot<T>) -> R) -> R {
+        ::critical_section::with(|cs| f(&mut self.slot.borrow(cs).borrow_mut()))
+    }
+
+    /// Stores `value` as the latest one, returning the value it replaces.
+    #[inline(always)]
+    pub fn send(&self, value: T) -> Option<T> {
+        self.lock(|slot| {
+            slot.generation = slot.generation.wrapping_add(1);
+            slot.value.replace(value)
+        })
+    }
+
+    /// Number of values sent so far. Wraps around on overflow.
+    #[inline(always)]
+    pub fn generation(&self) -> usize {
+        self.lock(|slot| slot.generation)
+    }
+
+    /// The latest value and its generation, or `None` if nothing was sent or it was taken.
+    #[inline(always)]
+    pub fn get(&self) -> Option<(T, usize)>
+    where
+        T: Clone,
+    {
+        self.lock(|slot| Some((slot.value.clone()?, slot.generation)))
+    }
+
+    /// Like [`Self::get`], but only if the generation differs from `seen`, i.e. a value was sent
+    /// since the reader last got one.
+    #[inline(always)]
+    pub fn get_changed(&self, seen: usize) -> Option<(T, usize)>
+    where
+        T: Clone,
+    {
+        self.lock(|slot| match slot.generation == seen {
+            true => None,
+            false => Some((slot.value.clone()?, slot.generation)),
+        })
+    }
+
+    /// Runs `f` on the latest value inside a critical section, to read it without a clone.
+    #[inline(always)]
+    pub fn with<R>(&self, f: impl FnOnce(Option<&T>) -> R) -> R {
+        self.lock(|slot| f(slot.value.as_ref()))
+    }
+
+    /// Removes the latest value; the generation is kept.
+    #[inline(always)]
+    pub fn take(&self) -> Option<T> {
+        self.lock(|slot| slot.value.take())
+    }
+}
+
+impl<T> Default for Watch<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Watch<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.lock(|slot| {
+            f.debug_struct("Watch")
+                .field("value", &slot.value)
+                .field("generation", &slot.generation)
+                .finish()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_latest_value_wins() {
+        static RPM: Watch<u32> = Watch::new();
+        assert_eq!(RPM.get(), None);
+        assert_eq!(RPM.send(4711), None);
+        assert_eq!(RPM.send(7690), Some(4711));
+        let (rpm, seen) = RPM.get().unwrap();
+        assert_eq!((rpm, seen), (7690, 2));
+        assert_eq!(RPM.get_changed(seen), None);
+
+        RPM.send(34129);
+        assert_eq!(RPM.get_changed(seen), Some((34129, 3)));
+        assert_eq!(RPM.with(|rpm| rpm.copied()), Some(34129));
+        assert_eq!(RPM.take(), Some(34129));
+        assert_eq!((RPM.get(), RPM.generation()), (None, 3));
+    }
+
+    #[test]
+    fn test_reader_never_sees_older_value() {
+        static SAMPLE: Watch<(u32, u32)> = Watch::new();
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 1..=1000 {
+                    SAMPLE.send((i, i * 2));
+                }
+            });
+            let mut last = 0;
+            while last < 1000 {
+                if let Some(((i, double), _)) = SAMPLE.get() {
+                    assert!(i >= last && double == i * 2);
+                    last = i;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,204 @@
+use crate::{OwnedStorage, Policy, QueueIndex, Storage, StorageQueue};
+use ::bytemuck::Pod;
+use core::fmt;
+use core::mem;
+
+/// Version written into the first byte of [`StorageQueue::to_bytes`] output.
+const WIRE_VERSION: u8 = 1;
+/// Set in the version byte when the elements are in big-endian byte order.
+const BIG_ENDIAN: u8 = 0x80;
+/// Version byte, element size as a little-endian `u16`, element count as a little-endian `u32`.
+const HEADER_LEN: usize = 7;
+
+/// Returned by [`StorageQueue::from_bytes`] when the bytes do not hold a queue it can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireError {
+    /// The bytes end before the header or the elements it announces.
+    Truncated,
+    /// The layout version is not one this crate writes.
+    UnsupportedVersion(u8),
+    /// The elements were written by a target of the other byte order. A `Pod` type has no
+    /// known field layout to swap, so they cannot be read back here.
+    ForeignByteOrder,
+    /// The elements were written for a type of a different size.
+    ElementSize { expected: usize, found: usize },
+    /// More elements than the queue can hold.
+    TooLong { len: usize, capacity: usize },
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Truncated => f.write_str("queue bytes are truncated"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported queue layout version {version}")
+            }
+            Self::ForeignByteOrder => f.write_str("queue elements are in the other byte order"),
+            Self::ElementSize { expected, found } => {
+                write!(f, "element size is {found} bytes, expected {expected}")
+            }
+            Self::TooLong { len, capacity } => {
+                write!(f, "{len} elements do not fit in a capacity of {capacity}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for WireError {}
+
+/// A compact layout for shipping a queue of plain-old-data elements to a host tool: a 7-byte
+/// header holding a version byte, the element size as a little-endian `u16` and the element
+/// count as a little-endian `u32`, followed by the elements, oldest first, in the target's own
+/// byte order. The top bit of the version byte is set when that order is big-endian, so the
+/// host knows which way to decode them.
+impl<T: Pod, S: Storage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Number of bytes [`Self::to_bytes`] writes.
+    #[inline(always)]
+    pub fn wire_len(&self) -> usize {
+        HEADER_LEN + self.len() * mem::size_of::<T>()
+    }
+
+    /// Writes the live elements in the layout described above. Returns the number of bytes
+    /// written, or 0 without writing anything if `out` is shorter than [`Self::wire_len`].
+    pub fn to_bytes(&self, out: &mut [u8]) -> usize {
+        let len = self.wire_len();
+        let Some(out) = out.get_mut(..len) else {
+            return 0;
+        };
+        let (header, mut body) = out.split_at_mut(HEADER_LEN);
+        header[0] = WIRE_VERSION | native_byte_order();
+        header[1..3].copy_from_slice(&(mem::size_of::<T>() as u16).to_le_bytes());
+        header[3..7].copy_from_slice(&(self.len() as u32).to_le_bytes());
+        let (front, back) = self.as_slices();
+        for half in [front, back] {
+            let bytes: &[u8] = ::bytemuck::cast_slice(half);
+            let (part, rest) = body.split_at_mut(bytes.len());
+            part.copy_from_slice(bytes);
+            body = rest;
+        }
+        len
+    }
+}
+
+impl<T: Pod, S: OwnedStorage<T>, I: QueueIndex, P: Policy> StorageQueue<T, S, I, P> {
+    /// Reads a queue written by [`Self::to_bytes`] on a target of the same byte order. Bytes
+    /// after the announced elements are ignored, so several queues can be sent back to back.
+    /// Storage that can be allocated at any size gets exactly as many slots as there are
+    /// elements.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        let header = bytes.get(..HEADER_LEN).ok_or(WireError::Truncated)?;
+        let version = header[0] & !BIG_ENDIAN;
+        if version != WIRE_VERSION {
+            return Err(WireError::UnsupportedVersion(version));
+        }
+        if header[0] & BIG_ENDIAN != native_byte_order() {
+            return Err(WireError::ForeignByteOrder);
+        }
+        let size = u16::from_le_bytes([header[1], header[2]]) as usize;
+        if size != mem::size_of::<T>() {
+            return Err(WireError::ElementSize {
+                expected: mem::size_of::<T>(),
+                found: size,
+            });
+        }
+        let len = u32::from_le_bytes([header[3], header[4], header[5], header[6]]) as usize;
+        if len > S::MAX_CAPACITY {
+            return Err(WireError::TooLong {
+                len,
+                capacity: S::MAX_CAPACITY,
+            });
+        }
+        let body = bytes
+            .get(HEADER_LEN..HEADER_LEN + len * size)
+            .ok_or(WireError::Truncated)?;
+        let mut queue = Self::from_storage(S::with_capacity(len));
+        // Zero-sized elements have no bytes to chunk, so count them out instead.
+        for n in 0..len {
+            let _ = queue.try_enqueue(::bytemuck::pod_read_unaligned(&body[n * size..][..size]));
+        }
+        Ok(queue)
+    }
+}
+
+/// [`BIG_ENDIAN`] if this target stores elements big-endian, otherwise 0.
+#[inline(always)]
+const fn native_byte_order() -> u8 {
+    if cfg!(target_endian = "big") {
+        BIG_ENDIAN
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Queue;
+
+    #[test]
+    fn test_wire_round_trip_across_wrap() {
+        let mut queue: Queue<u16, 4, u8> = Queue::from_array([26769, 64004, 63460]);
+        queue.dequeue();
+        queue.enqueue(857);
+        queue.enqueue(4711);
+
+        let mut out = [0xff; 20];
+        assert_eq!(queue.to_bytes(&mut out[..14]), 0);
+        assert_eq!(queue.to_bytes(&mut out), 15);
+        assert_eq!(out[..7], [1 | native_byte_order(), 2, 0, 4, 0, 0, 0]);
+        assert_eq!(
+            out[7..15],
+            [64004, 63460, 857, 4711].map(u16::to_ne_bytes).concat()
+        );
+
+        let restored: Queue<u16, 4> = Queue::from_bytes(&out).unwrap();
+        assert_eq!(restored, queue);
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_bad_input() {
+        let queue: Queue<u32, 2> = Queue::from_array([4711, 7690]);
+        let mut out = [0; 15];
+        queue.to_bytes(&mut out);
+
+        assert_eq!(
+            Queue::<u32, 2>::from_bytes(&out[..14]),
+            Err(WireError::Truncated)
+        );
+        assert_eq!(
+            Queue::<u32, 1>::from_bytes(&out),
+            Err(WireError::TooLong {
+                len: 2,
+                capacity: 1
+            })
+        );
+        assert_eq!(
+            Queue::<u16, 4>::from_bytes(&out),
+            Err(WireError::ElementSize {
+                expected: 2,
+                found: 4
+            })
+        );
+        out[0] ^= BIG_ENDIAN;
+        assert_eq!(
+            Queue::<u32, 2>::from_bytes(&out),
+            Err(WireError::ForeignByteOrder)
+        );
+        out[0] = 2 | native_byte_order();
+        assert_eq!(
+            Queue::<u32, 2>::from_bytes(&out),
+            Err(WireError::UnsupportedVersion(2))
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_boxed_from_bytes_fits_elements() {
+        let queue: Queue<u32, 8> = Queue::from_array([4711, 7690, 857]);
+        let mut out = [0; 19];
+        assert_eq!(queue.to_bytes(&mut out), 19);
+        let boxed: crate::BoxedQueue<u32> = crate::BoxedQueue::from_bytes(&out).unwrap();
+        assert_eq!(boxed.capacity(), 3);
+        assert_eq!(boxed, queue);
+    }
+}